@@ -0,0 +1,81 @@
+//! Compares serial and rayon rendering of id-range batches to locate the batch count where the
+//! pool pays for its hand-off. Run with `cargo bench --features parallel`.
+
+use std::{hint::black_box, time::Instant};
+
+use rayon::prelude::*;
+use sql_id_slicer::{BatchingMode, RangeBounds, SqlDialectKind, SqlParserBatchTemplate};
+
+/// Mirrors the chunk size the use case renders per parallel round.
+const CHUNK_SIZE: usize = 8_192;
+const BATCH_COUNTS: [usize; 8] = [1, 4, 16, 64, 256, 1_024, 8_192, 65_536];
+const ROUNDS: usize = 25;
+
+fn main() {
+    let sql_template = SqlParserBatchTemplate::parse(
+        "DELETE FROM users WHERE status = 'inactive' AND created_at < '2024-01-01'",
+        SqlDialectKind::MySql,
+        "id",
+        &[],
+        RangeBounds::InclusiveBetween,
+        BatchingMode::RangeBased,
+        false,
+    )
+    .expect("benchmark SQL should parse");
+
+    println!(
+        "threads: {}, best of {ROUNDS} rounds",
+        rayon::current_num_threads()
+    );
+    println!(
+        "{:>8} {:>12} {:>12} {:>7}",
+        "batches", "serial", "parallel", "ratio"
+    );
+    for batch_count in BATCH_COUNTS {
+        let serial = best_of(|| render_serially(&sql_template, batch_count));
+        let parallel = best_of(|| render_in_parallel(&sql_template, batch_count));
+        println!(
+            "{batch_count:>8} {:>10.1}µs {:>10.1}µs {:>7.2}",
+            serial * 1e6,
+            parallel * 1e6,
+            parallel / serial
+        );
+    }
+}
+
+fn best_of(mut run: impl FnMut() -> usize) -> f64 {
+    (0..ROUNDS)
+        .map(|_| {
+            let started_at = Instant::now();
+            black_box(run());
+            started_at.elapsed().as_secs_f64()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn render_serially(sql_template: &SqlParserBatchTemplate, batch_count: usize) -> usize {
+    (0..batch_count)
+        .map(|id| render(sql_template, id))
+        .map(|sql| sql.len())
+        .sum()
+}
+
+fn render_in_parallel(sql_template: &SqlParserBatchTemplate, batch_count: usize) -> usize {
+    let ids = (0..batch_count).collect::<Vec<_>>();
+    ids.chunks(CHUNK_SIZE)
+        .flat_map(|chunk| {
+            chunk
+                .par_iter()
+                .map(|&id| render(sql_template, id))
+                .collect::<Vec<_>>()
+        })
+        .map(|sql| sql.len())
+        .sum()
+}
+
+fn render(sql_template: &SqlParserBatchTemplate, id: usize) -> String {
+    let id = id as i128;
+    sql_template
+        .render_for_range(id * 1_000, id * 1_000 + 999)
+        .expect("benchmark batch should render")
+}
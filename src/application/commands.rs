@@ -1,6 +1,12 @@
 use std::path::PathBuf;
 
-use crate::domain::sql_dialect::SqlDialectKind;
+use crate::domain::{
+    date_batch::DateRange,
+    id_batch::{BatchingMode, RangeBounds},
+    sql_dialect::SqlDialectKind,
+    statement_terminator::StatementTerminator,
+    transaction_mode::TransactionMode,
+};
 
 #[derive(Debug)]
 pub struct GenerateBatchedSqlCommand {
@@ -10,12 +16,91 @@ pub struct GenerateBatchedSqlCommand {
     pub sleep_seconds: u64,
     pub raw_sql: String,
     pub output_path: PathBuf,
-    pub primary_key: String,
+    /// Streams the batches to stdout instead of creating `output_path`.
+    pub write_to_stdout: bool,
+    /// Column each batch condition ranges over: usually the primary key, but any indexed,
+    /// monotonic column such as `seq` works. Comma-separated for a composite key; every column
+    /// but the last is fixed by `key_prefix`.
+    pub range_column: String,
+    /// Fixed values for the leading columns of a composite `range_column`.
+    pub key_prefix: Vec<String>,
+    /// Rejects input SQL whose `WHERE` already compares the id column, instead of only warning.
+    pub strict_id_column: bool,
+    /// Silences the stderr warnings, such as the one for an UPDATE/DELETE without `WHERE`.
+    pub suppress_warnings: bool,
+    /// Quotes the primary key identifiers with the dialect's delimiter, e.g. `"order"` on Postgres.
+    pub quote_identifiers: bool,
     pub dialect_kind: SqlDialectKind,
+    pub transaction_mode: TransactionMode,
+    /// Ends each batch statement in SQL output; JSON records hold the bare statement.
+    pub statement_terminator: StatementTerminator,
+    pub range_bounds: RangeBounds,
+    /// Extends each id range this many ids past its boundary, or shortens it when negative.
+    pub overlap: i128,
+    /// Explicit ids to batch with `IN (...)`; when set, `start_id`/`end_id` only describe its bounds.
+    pub id_list: Option<Vec<i128>>,
+    /// Explicit non-numeric ids, such as UUIDs, batched as quoted `IN ('a', ...)` literals.
+    pub string_id_list: Option<Vec<String>>,
+    /// Emits one `pk % N = i` statement per shard instead of id ranges; `start_id`/`end_id` are unused.
+    pub shard_count: Option<usize>,
+    /// Slices `range_column` as a date column over this span instead of ids; `start_id`/`end_id`
+    /// are unused.
+    pub date_range: Option<DateRange>,
+    /// Writes at most this many batch statements per file, numbering files from `output_path`.
+    pub split_every: Option<usize>,
+    pub batching_mode: BatchingMode,
+    /// Emits id range or id list batches from the highest ids down; each batch covers the same ids.
+    pub descending: bool,
+    /// Prefixes each statement with a `-- batch i/n | id a..b` comment.
+    pub annotate: bool,
+    /// Stops after this many batches, to inspect the start of a large run.
+    pub batch_limit: Option<usize>,
+    /// Counts the batches and renders only the first and last one, without creating any file.
+    pub dry_run: bool,
+    pub output_format: OutputFormat,
+    /// Which rendered batches are parsed again before they are written.
+    pub validation: BatchValidation,
+    /// Precedes each UPDATE/DELETE batch with a commented-out `SELECT COUNT(*)` over the same rows.
+    pub with_verify: bool,
+    /// Adds to an existing output file instead of truncating it.
+    pub append: bool,
+}
+
+/// Layout of the generated output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One statement per line, with optional transactions, sleeps and annotations.
+    #[default]
+    Sql,
+    /// A JSON array of `{"index", "start_id", "end_id", "sql"}` records, one per batch.
+    Json,
+}
+
+/// How many rendered batches to re-parse as a safety net; each re-parse costs about a render.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchValidation {
+    #[default]
+    Off,
+    /// Only the first and the last batch.
+    FirstAndLast,
+    All,
 }
 
 #[derive(Debug)]
 pub struct GenerateBatchedSqlResult {
-    pub output_path: PathBuf,
+    /// `None` for a dry run, which writes nothing, and for stdout output.
+    pub output_path: Option<PathBuf>,
+    /// Batches written by this run only, also when appending to an existing file.
     pub batch_count: usize,
+    /// Batches the run would have written without `batch_limit`.
+    pub total_batch_count: usize,
+    pub files_written: Vec<PathBuf>,
+    /// Set by a dry run that produced at least one batch.
+    pub dry_run_preview: Option<DryRunPreview>,
+}
+
+#[derive(Debug)]
+pub struct DryRunPreview {
+    pub first_batch_sql: String,
+    pub last_batch_sql: String,
 }
@@ -0,0 +1,24 @@
+use std::{io, path::PathBuf};
+
+use thiserror::Error;
+
+use crate::domain::error::DomainError;
+
+/// Failure of [`GenerateBatchedSqlUseCase::execute`](crate::GenerateBatchedSqlUseCase::execute),
+/// separating a bad plan from a failure to write it.
+#[derive(Debug, Error)]
+pub enum GenerateBatchedSqlError {
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+    /// A combination of command options that cannot be honored.
+    #[error("{0}")]
+    InvalidCommand(String),
+    #[error("Unable to create file: {}", path.display())]
+    CreateFile {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Unable to write batched SQL")]
+    Write(#[from] io::Error),
+}
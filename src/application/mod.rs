@@ -1,2 +1,3 @@
 pub mod commands;
+pub mod error;
 pub mod use_cases;
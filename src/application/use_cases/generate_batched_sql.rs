@@ -1,68 +1,914 @@
 use std::{
-    fs::File,
-    io::{BufWriter, Write},
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result};
+#[cfg(feature = "gzip")]
+use flate2::{Compression, write::GzEncoder};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::Serialize;
 
 use crate::{
-    application::commands::{GenerateBatchedSqlCommand, GenerateBatchedSqlResult},
-    domain::id_batch::IdBatchSlicer,
+    application::{
+        commands::{
+            BatchValidation, DryRunPreview, GenerateBatchedSqlCommand, GenerateBatchedSqlResult,
+            OutputFormat,
+        },
+        error::GenerateBatchedSqlError,
+    },
+    domain::{
+        date_batch::{DateBatchRange, DateBatchSlicer},
+        error::DomainError,
+        id_batch::{
+            BatchingMode, IdBatchRange, IdBatchSlicer, IdListSlicer, IdShard, RangeBounds,
+            ShardSlicer,
+        },
+        transaction_mode::TransactionMode,
+    },
     infrastructure::sql_batch_template::SqlParserBatchTemplate,
 };
 
 #[derive(Debug, Default)]
 pub struct GenerateBatchedSqlUseCase;
 
+const MIN_SPLIT_FILE_INDEX_WIDTH: usize = 4;
+/// Below this many batches the thread pool hand-off costs more than rendering saves; see README.
+#[cfg(feature = "parallel")]
+const PARALLEL_RENDER_THRESHOLD: usize = 64;
+/// Batches rendered per parallel round, which bounds how much rendered SQL is buffered at once.
+#[cfg(feature = "parallel")]
+const PARALLEL_RENDER_CHUNK_SIZE: usize = 8_192;
+
+enum BatchSource {
+    IdRange(IdBatchSlicer),
+    IdList(IdListSlicer),
+    StringIdList(IdListSlicer<String>),
+    Shards(ShardSlicer),
+    Dates(DateBatchSlicer),
+    Limit(usize),
+}
+
+#[derive(Clone)]
+enum Batch {
+    IdRange(IdBatchRange),
+    IdList(Vec<i128>),
+    StringIdList(Vec<String>),
+    Shard(IdShard),
+    Date(DateBatchRange),
+    Limit(usize),
+}
+
+struct RenderedBatch {
+    batch: Batch,
+    sql: String,
+    verification_sql: Option<String>,
+}
+
+/// Destination of the generated SQL; gzip streams must be finished to write their trailer.
+enum OutputWriter {
+    Plain(Box<dyn Write>),
+    #[cfg(feature = "gzip")]
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutputWriter {
+    fn finish(self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(mut plain_writer) => plain_writer.flush(),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(gzip_writer) => gzip_writer.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputWriter::Plain(plain_writer) => plain_writer.write(buf),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(gzip_writer) => gzip_writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(plain_writer) => plain_writer.flush(),
+            #[cfg(feature = "gzip")]
+            OutputWriter::Gzip(gzip_writer) => gzip_writer.flush(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonBatchRecord<'a> {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_id: Option<i128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_id: Option<i128>,
+    sql: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verify_sql: Option<&'a str>,
+    /// Set when `verify_sql` dropped conditions on joined tables and may over-count.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    verify_approximate: bool,
+}
+
+impl Batch {
+    fn describe(&self, range_bounds: RangeBounds) -> String {
+        match (self, range_bounds) {
+            (Batch::IdRange(id_range), RangeBounds::InclusiveBetween) => {
+                format!("id {}..{}", id_range.start_id, id_range.end_id)
+            }
+            (Batch::IdRange(id_range), RangeBounds::HalfOpen) => {
+                format!("id {}..<{}", id_range.start_id, id_range.end_id)
+            }
+            (Batch::IdList(ids), _) => match (ids.first(), ids.last()) {
+                (Some(first_id), Some(last_id)) => {
+                    format!("ids {first_id}..{last_id} ({} values)", ids.len())
+                }
+                _ => "no ids".to_string(),
+            },
+            (Batch::StringIdList(ids), _) => match (ids.first(), ids.last()) {
+                (Some(first_id), Some(last_id)) => {
+                    format!("ids {first_id}..{last_id} ({} values)", ids.len())
+                }
+                _ => "no ids".to_string(),
+            },
+            (Batch::Shard(shard), _) => format!("shard {} of {}", shard.index, shard.count),
+            (Batch::Date(date_range), _) => {
+                format!("date {}..<{}", date_range.start_date, date_range.end_date)
+            }
+            (Batch::Limit(batch_size), _) => format!("limit {batch_size}"),
+        }
+    }
+
+    /// The numeric id bounds reported for the batch, `None` for string id, shard, date and
+    /// LIMIT-based batches.
+    fn id_bounds(&self) -> Option<(i128, i128)> {
+        match self {
+            Batch::IdRange(id_range) => Some((id_range.start_id, id_range.end_id)),
+            Batch::IdList(ids) => Some((*ids.first()?, *ids.last()?)),
+            Batch::StringIdList(_) | Batch::Shard(_) | Batch::Date(_) | Batch::Limit(_) => None,
+        }
+    }
+}
+
+impl BatchSource {
+    fn batch_count(&self) -> usize {
+        match self {
+            BatchSource::IdRange(id_batch_slicer) => id_batch_slicer.count_batches(),
+            BatchSource::IdList(id_list_slicer) => id_list_slicer.count_batches(),
+            BatchSource::StringIdList(id_list_slicer) => id_list_slicer.count_batches(),
+            BatchSource::Shards(shard_slicer) => shard_slicer.count_batches(),
+            BatchSource::Dates(date_batch_slicer) => date_batch_slicer.count_batches(),
+            BatchSource::Limit(_) => 1,
+        }
+    }
+
+    fn into_batches(self) -> Box<dyn Iterator<Item = Batch>> {
+        match self {
+            BatchSource::IdRange(id_batch_slicer) => {
+                Box::new(id_batch_slicer.iter_ranges().map(Batch::IdRange))
+            }
+            BatchSource::IdList(id_list_slicer) => {
+                Box::new(id_list_slicer.into_batches().map(Batch::IdList))
+            }
+            BatchSource::StringIdList(id_list_slicer) => {
+                Box::new(id_list_slicer.into_batches().map(Batch::StringIdList))
+            }
+            BatchSource::Shards(shard_slicer) => {
+                Box::new(shard_slicer.iter_shards().map(Batch::Shard))
+            }
+            BatchSource::Dates(date_batch_slicer) => {
+                Box::new(date_batch_slicer.iter_ranges().map(Batch::Date))
+            }
+            BatchSource::Limit(batch_size) => Box::new(std::iter::once(Batch::Limit(batch_size))),
+        }
+    }
+
+    /// The first and last batch, computed without walking the id range in between.
+    fn into_first_and_last_batches(self) -> Option<(Batch, Batch)> {
+        match self {
+            BatchSource::IdRange(id_batch_slicer) => Some((
+                Batch::IdRange(id_batch_slicer.iter_ranges().next()?),
+                Batch::IdRange(id_batch_slicer.last_range()),
+            )),
+            BatchSource::IdList(id_list_slicer) => {
+                let mut batches = id_list_slicer.into_batches().map(Batch::IdList);
+                let first_batch = batches.next()?;
+                let last_batch = batches.last().unwrap_or_else(|| first_batch.clone());
+                Some((first_batch, last_batch))
+            }
+            BatchSource::StringIdList(id_list_slicer) => {
+                let mut batches = id_list_slicer.into_batches().map(Batch::StringIdList);
+                let first_batch = batches.next()?;
+                let last_batch = batches.last().unwrap_or_else(|| first_batch.clone());
+                Some((first_batch, last_batch))
+            }
+            BatchSource::Shards(shard_slicer) => {
+                let mut shards = shard_slicer.iter_shards().map(Batch::Shard);
+                let first_batch = shards.next()?;
+                let last_batch = shards.last().unwrap_or_else(|| first_batch.clone());
+                Some((first_batch, last_batch))
+            }
+            BatchSource::Dates(date_batch_slicer) => {
+                let mut date_ranges = date_batch_slicer.iter_ranges().map(Batch::Date);
+                let first_batch = date_ranges.next()?;
+                let last_batch = date_ranges.last().unwrap_or_else(|| first_batch.clone());
+                Some((first_batch, last_batch))
+            }
+            BatchSource::Limit(batch_size) => {
+                Some((Batch::Limit(batch_size), Batch::Limit(batch_size)))
+            }
+        }
+    }
+}
+
 impl GenerateBatchedSqlUseCase {
-    pub fn execute(&self, command: GenerateBatchedSqlCommand) -> Result<GenerateBatchedSqlResult> {
-        let id_batch_slicer =
-            IdBatchSlicer::new(command.start_id, command.end_id, command.batch_size)?;
-
-        let sql_template = SqlParserBatchTemplate::parse(
-            &command.raw_sql,
-            command.dialect_kind,
-            &command.primary_key,
-        )?;
+    /// Renders every batch statement in memory, in emission order, without touching the filesystem.
+    pub fn render_batches(
+        &self,
+        command: &GenerateBatchedSqlCommand,
+    ) -> Result<impl Iterator<Item = Result<String, DomainError>> + use<>, DomainError> {
+        let batch_source = build_batch_source(command)?;
+        let sql_template = parse_batch_template(command)?;
+        let batch_count = limit_batch_count(command, batch_source.batch_count());
+        let rendered_batches =
+            render_batch_details(batch_source, sql_template, batch_count, command.with_verify);
+        Ok(rendered_batches.map(|rendered_batch| rendered_batch.map(|batch| batch.sql)))
+    }
+
+    /// Renders the `SELECT MIN(pk), MAX(pk)` query whose result gives `start_id`/`end_id`.
+    pub fn render_range_bootstrap_query(
+        &self,
+        command: &GenerateBatchedSqlCommand,
+    ) -> Result<String, DomainError> {
+        parse_batch_template(command)?.render_range_bootstrap_query()
+    }
+
+    pub fn execute(
+        &self,
+        command: GenerateBatchedSqlCommand,
+    ) -> Result<GenerateBatchedSqlResult, GenerateBatchedSqlError> {
+        self.execute_with_warnings_to(command, &mut io::stderr())
+    }
+
+    /// `execute` with the input warnings written to `warning_writer` instead of stderr.
+    fn execute_with_warnings_to(
+        &self,
+        command: GenerateBatchedSqlCommand,
+        warning_writer: &mut dyn Write,
+    ) -> Result<GenerateBatchedSqlResult, GenerateBatchedSqlError> {
+        if command.transaction_mode.group_size() == Some(0) {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Transaction group size must be greater than 0".to_string(),
+            ));
+        }
+        if command.batch_limit == Some(0) {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Batch limit must be greater than 0".to_string(),
+            ));
+        }
+        if command.split_every == Some(0) {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Split size must be greater than 0".to_string(),
+            ));
+        }
+        if command.write_to_stdout && command.split_every.is_some() {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Split output files cannot be combined with stdout output".to_string(),
+            ));
+        }
+        if command.output_format == OutputFormat::Json
+            && command.transaction_mode != TransactionMode::None
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Transactions cannot be combined with JSON output; each record holds one statement"
+                    .to_string(),
+            ));
+        }
+        if command.append && command.output_format == OutputFormat::Json {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Appending cannot be combined with JSON output; the file would hold two arrays"
+                    .to_string(),
+            ));
+        }
+        if command.shard_count.is_some()
+            && (command.id_list.is_some() || command.batching_mode == BatchingMode::LimitBased)
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Shards cannot be combined with an id list or LIMIT-based batching".to_string(),
+            ));
+        }
+        if command.string_id_list.is_some()
+            && (command.id_list.is_some()
+                || command.shard_count.is_some()
+                || command.date_range.is_some()
+                || command.batching_mode == BatchingMode::LimitBased)
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "String ids cannot be combined with numeric ids, shards, date ranges or LIMIT-based batching"
+                    .to_string(),
+            ));
+        }
+        if command.date_range.is_some()
+            && (command.id_list.is_some()
+                || command.shard_count.is_some()
+                || command.batching_mode == BatchingMode::LimitBased)
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Date ranges cannot be combined with an id list, shards or LIMIT-based batching"
+                    .to_string(),
+            ));
+        }
+        if command.overlap != 0
+            && (command.id_list.is_some()
+                || command.string_id_list.is_some()
+                || command.shard_count.is_some()
+                || command.date_range.is_some()
+                || command.batching_mode == BatchingMode::LimitBased)
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Overlap only applies to id range batches".to_string(),
+            ));
+        }
+        if command.descending
+            && (command.shard_count.is_some()
+                || command.date_range.is_some()
+                || command.batching_mode == BatchingMode::LimitBased)
+        {
+            return Err(GenerateBatchedSqlError::InvalidCommand(
+                "Descending order only applies to id range and id list batches".to_string(),
+            ));
+        }
+        let sql_template = parse_batch_template(&command)?;
+        if !command.suppress_warnings {
+            print_input_sql_warnings(&command, &sql_template, warning_writer)?;
+        }
+        let batch_source = build_batch_source(&command)?;
+        let total_batch_count = batch_source.batch_count();
+        let batch_count = limit_batch_count(&command, total_batch_count);
+        // A dry run previews the real run, so it shows the same warnings first.
+        if command.dry_run {
+            return Ok(execute_dry_run(
+                &command,
+                &sql_template,
+                batch_source,
+                batch_count,
+                total_batch_count,
+            )?);
+        }
 
-        let output_file = File::create(&command.output_path)
-            .with_context(|| format!("Unable to create file: {}", command.output_path.display()))?;
-        let mut output_writer = BufWriter::new(output_file);
-        let ranges = id_batch_slicer.iter_ranges().collect::<Vec<_>>();
-        let sleep_statement = command.dialect_kind.sleep_statement(command.sleep_seconds);
-        if command.sleep_seconds > 0
-            && sleep_statement.is_none()
+        let mut rendered_batches = render_batch_details(
+            batch_source,
+            sql_template.clone(),
+            batch_count,
+            command.with_verify,
+        )
+        .peekable();
+        let file_index_width = match command.split_every {
+            Some(split_every) => {
+                let file_count = batch_count.div_ceil(split_every).max(1);
+                Some(file_count.to_string().len().max(MIN_SPLIT_FILE_INDEX_WIDTH))
+            }
+            None => None,
+        };
+        let (mut output_writer, mut files_written) = if command.write_to_stdout {
+            let stdout_writer = OutputWriter::Plain(Box::new(BufWriter::new(io::stdout().lock())));
+            (stdout_writer, Vec::new())
+        } else {
+            let first_output_path = match file_index_width {
+                Some(width) => build_split_output_path(&command.output_path, 1, width),
+                None => command.output_path.clone(),
+            };
+            (
+                create_output_writer(&first_output_path, command.append)?,
+                vec![first_output_path],
+            )
+        };
+        let sleep_statement = command
+            .dialect_kind
+            .sleep_statement_or_comment(command.sleep_seconds);
+        if !command.suppress_warnings
+            && command.output_format == OutputFormat::Sql
+            && command.sleep_seconds > 0
             && let Some(reason) = command.dialect_kind.sleep_unsupported_reason()
         {
-            eprintln!(
-                "Warning: sleep_seconds is set to {}, but dialect '{}' does not support SQL sleep; {}.",
+            writeln!(
+                warning_writer,
+                "Warning: sleep_seconds is set to {}, but dialect '{}' does not support SQL sleep; {}. A comment is written between batches instead.",
                 command.sleep_seconds, command.dialect_kind, reason
-            );
+            )?;
         }
 
+        let transaction_group_size = command.transaction_mode.group_size();
+        let verification_is_approximate =
+            command.with_verify && sql_template.verification_is_approximate();
+
         let mut generated_batch_count = 0usize;
-        for (index, id_range) in ranges.iter().enumerate() {
-            let mut rendered_sql =
-                sql_template.render_for_range(id_range.start_id, id_range.end_id)?;
-            if !rendered_sql.trim_end().ends_with(';') {
-                rendered_sql.push(';');
+        while let Some(rendered_batch) = rendered_batches.next() {
+            let rendered_batch = rendered_batch?;
+            let rendered_sql = &rendered_batch.sql;
+
+            let index = generated_batch_count;
+            let is_last_batch = rendered_batches.peek().is_none();
+            if should_validate_batch(command.validation, index, is_last_batch) {
+                validate_rendered_batch(&sql_template, index, rendered_sql)?;
+            }
+            let index_in_file = command
+                .split_every
+                .map_or(index, |split_every| index % split_every);
+            if index_in_file == 0
+                && index > 0
+                && let (Some(split_every), Some(width)) = (command.split_every, file_index_width)
+            {
+                let output_path =
+                    build_split_output_path(&command.output_path, index / split_every + 1, width);
+                let finished_writer = std::mem::replace(
+                    &mut output_writer,
+                    create_output_writer(&output_path, command.append)?,
+                );
+                finished_writer.finish()?;
+                files_written.push(output_path);
+            }
+
+            // Transactions and sleeps never span files, so every split file runs on its own.
+            let has_next_batch_in_file = rendered_batches.peek().is_some()
+                && command
+                    .split_every
+                    .is_none_or(|split_every| index_in_file + 1 < split_every);
+            if command.output_format == OutputFormat::Json {
+                write_json_record(
+                    &mut output_writer,
+                    index,
+                    index_in_file,
+                    has_next_batch_in_file,
+                    &rendered_batch,
+                    verification_is_approximate,
+                )?;
+                generated_batch_count += 1;
+                continue;
+            }
+
+            if let Some(group_size) = transaction_group_size
+                && index_in_file.is_multiple_of(group_size)
+            {
+                writeln!(
+                    output_writer,
+                    "{}",
+                    command.dialect_kind.begin_transaction_statement()
+                )?;
+            }
+            if command.annotate {
+                let header = format!(
+                    "batch {}/{} | {}",
+                    index + 1,
+                    total_batch_count,
+                    rendered_batch.batch.describe(command.range_bounds)
+                );
+                writeln!(
+                    output_writer,
+                    "{}",
+                    command.dialect_kind.line_comment(&header)
+                )?;
+            }
+            if let Some(verification_sql) = &rendered_batch.verification_sql {
+                let verification_label = if verification_is_approximate {
+                    "verify (approximate, conditions on joined tables dropped)"
+                } else {
+                    "verify"
+                };
+                writeln!(
+                    output_writer,
+                    "{}",
+                    command
+                        .dialect_kind
+                        .line_comment(&format!("{verification_label}: {verification_sql};"))
+                )?;
+            }
+            writeln!(
+                output_writer,
+                "{}",
+                command.statement_terminator.terminate(rendered_sql)
+            )?;
+            // The last group may be partial, so it is closed even when it is not full.
+            if let Some(group_size) = transaction_group_size
+                && ((index_in_file + 1).is_multiple_of(group_size) || !has_next_batch_in_file)
+            {
+                writeln!(
+                    output_writer,
+                    "{}",
+                    command.dialect_kind.commit_transaction_statement()
+                )?;
             }
-            writeln!(output_writer, "{rendered_sql}")?;
 
-            let has_next_batch = index + 1 < ranges.len();
-            if has_next_batch && let Some(statement) = &sleep_statement {
+            if has_next_batch_in_file && let Some(statement) = &sleep_statement {
                 writeln!(output_writer, "{statement}")?;
             }
 
             generated_batch_count += 1;
         }
-        output_writer.flush()?;
+        if command.output_format == OutputFormat::Json && generated_batch_count == 0 {
+            writeln!(output_writer, "[]")?;
+        }
+        output_writer.finish()?;
 
         Ok(GenerateBatchedSqlResult {
-            output_path: command.output_path,
+            output_path: (!command.write_to_stdout).then_some(command.output_path),
             batch_count: generated_batch_count,
+            total_batch_count,
+            files_written,
+            dry_run_preview: None,
+        })
+    }
+}
+
+fn execute_dry_run(
+    command: &GenerateBatchedSqlCommand,
+    sql_template: &SqlParserBatchTemplate,
+    batch_source: BatchSource,
+    batch_count: usize,
+    total_batch_count: usize,
+) -> Result<GenerateBatchedSqlResult, DomainError> {
+    let first_and_last_batches = if batch_count < total_batch_count {
+        let mut batches = batch_source.into_batches().take(batch_count);
+        batches.next().map(|first_batch| {
+            let last_batch = batches.last().unwrap_or_else(|| first_batch.clone());
+            (first_batch, last_batch)
+        })
+    } else {
+        batch_source.into_first_and_last_batches()
+    };
+    let dry_run_preview = match first_and_last_batches {
+        Some((first_batch, last_batch)) => {
+            let first_batch_sql = render_batch(sql_template, first_batch, false)?.sql;
+            let last_batch_sql = render_batch(sql_template, last_batch, false)?.sql;
+            if command.validation != BatchValidation::Off {
+                validate_rendered_batch(sql_template, 0, &first_batch_sql)?;
+                validate_rendered_batch(sql_template, batch_count - 1, &last_batch_sql)?;
+            }
+            Some(DryRunPreview {
+                first_batch_sql,
+                last_batch_sql,
+            })
+        }
+        None => None,
+    };
+
+    Ok(GenerateBatchedSqlResult {
+        output_path: None,
+        batch_count,
+        total_batch_count,
+        files_written: Vec::new(),
+        dry_run_preview,
+    })
+}
+
+/// `batch_count` is the number of batches to render, already capped by `batch_limit`.
+fn render_batch_details(
+    batch_source: BatchSource,
+    sql_template: SqlParserBatchTemplate,
+    batch_count: usize,
+    with_verify: bool,
+) -> Box<dyn Iterator<Item = Result<RenderedBatch, DomainError>>> {
+    let batches = batch_source.into_batches().take(batch_count);
+
+    #[cfg(feature = "parallel")]
+    if renders_in_parallel(batch_count) {
+        return render_in_parallel(batches, sql_template, with_verify);
+    }
+
+    Box::new(batches.map(move |batch| render_batch(&sql_template, batch, with_verify)))
+}
+
+fn limit_batch_count(command: &GenerateBatchedSqlCommand, total_batch_count: usize) -> usize {
+    command
+        .batch_limit
+        .map_or(total_batch_count, |batch_limit| {
+            batch_limit.min(total_batch_count)
+        })
+}
+
+fn parse_batch_template(
+    command: &GenerateBatchedSqlCommand,
+) -> Result<SqlParserBatchTemplate, DomainError> {
+    let sql_template = SqlParserBatchTemplate::parse(
+        &command.raw_sql,
+        command.dialect_kind,
+        &command.range_column,
+        &command.key_prefix,
+        command.range_bounds,
+        command.batching_mode,
+        command.quote_identifiers,
+    )?;
+    if command.strict_id_column
+        && let Some(predicate) = find_conflicting_id_column_predicate(command, &sql_template)
+    {
+        return Err(DomainError::ConstrainedIdColumn(predicate));
+    }
+    Ok(sql_template)
+}
+
+fn print_input_sql_warnings(
+    command: &GenerateBatchedSqlCommand,
+    sql_template: &SqlParserBatchTemplate,
+    warning_writer: &mut dyn Write,
+) -> io::Result<()> {
+    if !command.strict_id_column
+        && let Some(predicate) = find_conflicting_id_column_predicate(command, sql_template)
+    {
+        writeln!(
+            warning_writer,
+            "Warning: input SQL already constrains the id column with `{predicate}`; each batch condition is added on top of it."
+        )?;
+    }
+    // A LIMIT batch orders by the key but adds no condition on it.
+    if command.batching_mode != BatchingMode::LimitBased
+        && let Some(key_expr) = sql_template.find_unfiltered_dml_key()
+    {
+        writeln!(
+            warning_writer,
+            "Warning: input SQL has no WHERE clause, so only the injected condition on `{key_expr}` limits each batch; make sure the table has this column."
+        )?;
+    }
+    Ok(())
+}
+
+/// A key predicate only conflicts with batches that bound the key; LIMIT and shard statements
+/// rely on the input `WHERE` to narrow the rows.
+fn find_conflicting_id_column_predicate(
+    command: &GenerateBatchedSqlCommand,
+    sql_template: &SqlParserBatchTemplate,
+) -> Option<String> {
+    if command.batching_mode == BatchingMode::LimitBased || command.shard_count.is_some() {
+        return None;
+    }
+    sql_template.find_id_column_predicate()
+}
+
+fn should_validate_batch(validation: BatchValidation, index: usize, is_last_batch: bool) -> bool {
+    match validation {
+        BatchValidation::Off => false,
+        BatchValidation::FirstAndLast => index == 0 || is_last_batch,
+        BatchValidation::All => true,
+    }
+}
+
+fn validate_rendered_batch(
+    sql_template: &SqlParserBatchTemplate,
+    index: usize,
+    rendered_sql: &str,
+) -> Result<(), DomainError> {
+    sql_template
+        .validate_rendered_sql(rendered_sql)
+        .map_err(|error| DomainError::InvalidRenderedBatch {
+            batch_number: index + 1,
+            reason: error.to_string(),
         })
+}
+
+fn render_batch(
+    sql_template: &SqlParserBatchTemplate,
+    batch: Batch,
+    with_verify: bool,
+) -> Result<RenderedBatch, DomainError> {
+    let sql = match &batch {
+        Batch::IdRange(id_range) => {
+            sql_template.render_for_range(id_range.start_id, id_range.end_id)
+        }
+        Batch::IdList(ids) => sql_template.render_for_ids(ids),
+        Batch::StringIdList(ids) => sql_template.render_for_string_ids(ids),
+        Batch::Shard(shard) => sql_template.render_for_shard(*shard),
+        Batch::Date(date_range) => sql_template.render_for_date_range(*date_range),
+        Batch::Limit(batch_size) => sql_template.render_with_limit(*batch_size),
+    }?;
+    let verification_sql = match &batch {
+        _ if !with_verify => None,
+        Batch::IdRange(id_range) => {
+            sql_template.render_verification_for_range(id_range.start_id, id_range.end_id)?
+        }
+        Batch::IdList(ids) => sql_template.render_verification_for_ids(ids)?,
+        Batch::StringIdList(ids) => sql_template.render_verification_for_string_ids(ids)?,
+        Batch::Shard(shard) => sql_template.render_verification_for_shard(*shard)?,
+        Batch::Date(date_range) => sql_template.render_verification_for_date_range(*date_range)?,
+        // A LIMIT batch has no id condition, so there is nothing narrower to count.
+        Batch::Limit(_) => None,
+    };
+    Ok(RenderedBatch {
+        batch,
+        sql,
+        verification_sql,
+    })
+}
+
+#[cfg(feature = "parallel")]
+fn renders_in_parallel(batch_count: usize) -> bool {
+    batch_count >= PARALLEL_RENDER_THRESHOLD
+}
+
+/// Renders chunks of batches on the rayon pool; `collect` keeps each chunk in emission order.
+#[cfg(feature = "parallel")]
+fn render_in_parallel(
+    mut batches: impl Iterator<Item = Batch> + 'static,
+    sql_template: SqlParserBatchTemplate,
+    with_verify: bool,
+) -> Box<dyn Iterator<Item = Result<RenderedBatch, DomainError>>> {
+    let rendered_chunks = std::iter::from_fn(move || {
+        let chunk = batches
+            .by_ref()
+            .take(PARALLEL_RENDER_CHUNK_SIZE)
+            .collect::<Vec<_>>();
+        if chunk.is_empty() {
+            return None;
+        }
+        Some(
+            chunk
+                .into_par_iter()
+                .map(|batch| render_batch(&sql_template, batch, with_verify))
+                .collect::<Vec<_>>(),
+        )
+    });
+    Box::new(rendered_chunks.flatten())
+}
+
+/// Fails with `NoBatches` rather than letting an empty id list produce an empty output file.
+fn build_batch_source(command: &GenerateBatchedSqlCommand) -> Result<BatchSource, DomainError> {
+    let batch_source = select_batch_source(command)?;
+    if batch_source.batch_count() == 0 {
+        return Err(DomainError::NoBatches);
+    }
+    Ok(batch_source)
+}
+
+fn select_batch_source(command: &GenerateBatchedSqlCommand) -> Result<BatchSource, DomainError> {
+    if command.batching_mode == BatchingMode::LimitBased {
+        if command.batch_size == 0 {
+            return Err(DomainError::InvalidBatchSize);
+        }
+        return Ok(BatchSource::Limit(command.batch_size));
+    }
+
+    if let Some(shard_count) = command.shard_count {
+        return Ok(BatchSource::Shards(ShardSlicer::new(shard_count)?));
+    }
+
+    if let Some(date_range) = command.date_range {
+        return Ok(BatchSource::Dates(DateBatchSlicer::new(date_range)?));
+    }
+
+    if let Some(ids) = &command.string_id_list {
+        let id_list_slicer = IdListSlicer::new(ids.clone(), command.batch_size)?;
+        return Ok(BatchSource::StringIdList(if command.descending {
+            id_list_slicer.in_descending_order()
+        } else {
+            id_list_slicer
+        }));
+    }
+
+    match &command.id_list {
+        Some(ids) => {
+            let id_list_slicer = IdListSlicer::new(ids.clone(), command.batch_size)?;
+            Ok(BatchSource::IdList(if command.descending {
+                id_list_slicer.in_descending_order()
+            } else {
+                id_list_slicer
+            }))
+        }
+        None => {
+            let id_batch_slicer = IdBatchSlicer::new(
+                command.start_id,
+                command.end_id,
+                command.batch_size,
+                command.range_bounds,
+            )?
+            .with_overlap(command.overlap)?;
+            Ok(BatchSource::IdRange(if command.descending {
+                id_batch_slicer.in_descending_order()
+            } else {
+                id_batch_slicer
+            }))
+        }
+    }
+}
+
+/// Streams one record of a JSON array, opening and closing the array at the file's edges.
+fn write_json_record(
+    output_writer: &mut dyn Write,
+    index: usize,
+    index_in_file: usize,
+    has_next_batch_in_file: bool,
+    rendered_batch: &RenderedBatch,
+    verification_is_approximate: bool,
+) -> io::Result<()> {
+    let id_bounds = rendered_batch.batch.id_bounds();
+    let record = JsonBatchRecord {
+        index: index + 1,
+        start_id: id_bounds.map(|(start_id, _)| start_id),
+        end_id: id_bounds.map(|(_, end_id)| end_id),
+        sql: &rendered_batch.sql,
+        verify_sql: rendered_batch.verification_sql.as_deref(),
+        verify_approximate: verification_is_approximate
+            && rendered_batch.verification_sql.is_some(),
+    };
+
+    if index_in_file == 0 {
+        writeln!(output_writer, "[")?;
+    }
+    serde_json::to_writer(&mut *output_writer, &record)?;
+    if has_next_batch_in_file {
+        writeln!(output_writer, ",")?;
+    } else {
+        writeln!(output_writer, "\n]")?;
     }
+    Ok(())
+}
+
+fn create_output_writer(
+    output_path: &Path,
+    append: bool,
+) -> Result<OutputWriter, GenerateBatchedSqlError> {
+    let to_create_file_error = |source| GenerateBatchedSqlError::CreateFile {
+        path: output_path.to_path_buf(),
+        source,
+    };
+    let is_gzip = is_gzip_path(output_path);
+    #[cfg(not(feature = "gzip"))]
+    if is_gzip {
+        return Err(GenerateBatchedSqlError::InvalidCommand(format!(
+            "Writing {} requires building with the `gzip` feature",
+            output_path.display()
+        )));
+    }
+
+    let output_file = if append {
+        let mut output_file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(output_path)
+            .map_err(to_create_file_error)?;
+        // Concatenated gzip members decompress as one stream, so only plain files need the check.
+        if !is_gzip && !ends_with_newline_or_is_empty(&mut output_file)? {
+            writeln!(output_file)?;
+        }
+        output_file
+    } else {
+        File::create(output_path).map_err(to_create_file_error)?
+    };
+
+    #[cfg(feature = "gzip")]
+    if is_gzip {
+        return Ok(OutputWriter::Gzip(GzEncoder::new(
+            BufWriter::new(output_file),
+            Compression::default(),
+        )));
+    }
+    Ok(OutputWriter::Plain(Box::new(BufWriter::new(output_file))))
+}
+
+fn is_gzip_path(output_path: &Path) -> bool {
+    output_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+}
+
+/// Appended batches must start on their own line, even if a previous run left a partial one.
+fn ends_with_newline_or_is_empty(output_file: &mut File) -> io::Result<bool> {
+    if output_file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    let mut last_byte = [0u8; 1];
+    output_file.seek(SeekFrom::End(-1))?;
+    output_file.read_exact(&mut last_byte)?;
+    Ok(last_byte[0] == b'\n')
+}
+
+/// Turns `id_slice.sql` into `id_slice.0001.sql`, padding the index so lexical order is numeric.
+/// A `.gz` suffix stays last: `id_slice.sql.gz` becomes `id_slice.0001.sql.gz`.
+fn build_split_output_path(output_path: &Path, file_index: usize, width: usize) -> PathBuf {
+    if is_gzip_path(output_path) {
+        let uncompressed_path = output_path.with_extension("");
+        let split_path = build_split_output_path(&uncompressed_path, file_index, width);
+        let mut split_file_name = split_path.file_name().unwrap_or_default().to_os_string();
+        split_file_name.push(".gz");
+        return split_path.with_file_name(split_file_name);
+    }
+
+    let file_stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match output_path.extension() {
+        Some(extension) => format!(
+            "{file_stem}.{file_index:0width$}.{}",
+            extension.to_string_lossy()
+        ),
+        None => format!("{file_stem}.{file_index:0width$}"),
+    };
+    output_path.with_file_name(file_name)
 }
 
 #[cfg(test)]
@@ -74,10 +920,27 @@ mod tests {
     };
 
     use crate::{
-        application::commands::GenerateBatchedSqlCommand, domain::sql_dialect::SqlDialectKind,
+        application::{
+            commands::{BatchValidation, GenerateBatchedSqlCommand, OutputFormat},
+            error::GenerateBatchedSqlError,
+        },
+        domain::{
+            date_batch::{DateRange, DateStep},
+            error::DomainError,
+            id_batch::{BatchingMode, RangeBounds},
+            sql_dialect::SqlDialectKind,
+            statement_terminator::StatementTerminator,
+            transaction_mode::TransactionMode,
+        },
     };
 
-    use super::GenerateBatchedSqlUseCase;
+    #[cfg(feature = "gzip")]
+    use std::{fs::File, io::Read};
+
+    use super::{
+        GenerateBatchedSqlUseCase, build_split_output_path, parse_batch_template,
+        should_validate_batch, validate_rendered_batch,
+    };
 
     fn build_temp_output_path() -> PathBuf {
         let unique_suffix = SystemTime::now()
@@ -87,18 +950,49 @@ mod tests {
         std::env::temp_dir().join(format!("sql_id_slicer_use_case_{unique_suffix}.sql"))
     }
 
+    fn build_delete_command(output_path: PathBuf) -> GenerateBatchedSqlCommand {
+        GenerateBatchedSqlCommand {
+            start_id: 1,
+            end_id: 2,
+            batch_size: 1,
+            sleep_seconds: 0,
+            raw_sql: "DELETE FROM users".to_string(),
+            output_path,
+            write_to_stdout: false,
+            range_column: "id".to_string(),
+            key_prefix: Vec::new(),
+            strict_id_column: false,
+            suppress_warnings: false,
+            quote_identifiers: false,
+            dialect_kind: SqlDialectKind::MySql,
+            transaction_mode: TransactionMode::None,
+            statement_terminator: StatementTerminator::Semicolon,
+            range_bounds: RangeBounds::InclusiveBetween,
+            overlap: 0,
+            id_list: None,
+            string_id_list: None,
+            shard_count: None,
+            date_range: None,
+            split_every: None,
+            batching_mode: BatchingMode::RangeBased,
+            descending: false,
+            annotate: false,
+            batch_limit: None,
+            dry_run: false,
+            output_format: OutputFormat::Sql,
+            validation: BatchValidation::Off,
+            with_verify: false,
+            append: false,
+        }
+    }
+
     #[test]
     fn inserts_sleep_between_batches_when_enabled() {
         let output_path = build_temp_output_path();
         let command = GenerateBatchedSqlCommand {
-            start_id: 1,
             end_id: 3,
-            batch_size: 1,
             sleep_seconds: 1,
-            raw_sql: "DELETE FROM users".to_string(),
-            output_path: output_path.clone(),
-            primary_key: "id".to_string(),
-            dialect_kind: SqlDialectKind::MySql,
+            ..build_delete_command(output_path.clone())
         };
 
         let result = GenerateBatchedSqlUseCase
@@ -118,16 +1012,7 @@ mod tests {
     #[test]
     fn does_not_insert_sleep_when_disabled() {
         let output_path = build_temp_output_path();
-        let command = GenerateBatchedSqlCommand {
-            start_id: 1,
-            end_id: 2,
-            batch_size: 1,
-            sleep_seconds: 0,
-            raw_sql: "DELETE FROM users".to_string(),
-            output_path: output_path.clone(),
-            primary_key: "id".to_string(),
-            dialect_kind: SqlDialectKind::MySql,
-        };
+        let command = build_delete_command(output_path.clone());
 
         GenerateBatchedSqlUseCase
             .execute(command)
@@ -139,17 +1024,12 @@ mod tests {
     }
 
     #[test]
-    fn does_not_insert_sleep_for_unsupported_dialect() {
+    fn writes_sleep_comment_for_unsupported_dialect() {
         let output_path = build_temp_output_path();
         let command = GenerateBatchedSqlCommand {
-            start_id: 1,
-            end_id: 2,
-            batch_size: 1,
             sleep_seconds: 1,
-            raw_sql: "DELETE FROM users".to_string(),
-            output_path: output_path.clone(),
-            primary_key: "id".to_string(),
             dialect_kind: SqlDialectKind::Sqlite,
+            ..build_delete_command(output_path.clone())
         };
 
         GenerateBatchedSqlUseCase
@@ -160,7 +1040,996 @@ mod tests {
         assert!(!content.contains("pg_sleep("));
         assert!(!content.contains("WAITFOR DELAY"));
         assert!(!content.contains("SYSTEM$WAIT"));
+        assert_eq!(
+            content,
+            "DELETE FROM users WHERE id BETWEEN 1 AND 1;\n-- sleep 1s between batches (not supported by sqlite)\nDELETE FROM users WHERE id BETWEEN 2 AND 2;\n"
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn wraps_every_n_batches_and_commits_final_partial_group() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 5,
+            transaction_mode: TransactionMode::EveryN(2),
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;",
+                "COMMIT;",
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 3 AND 3;",
+                "DELETE FROM users WHERE id BETWEEN 4 AND 4;",
+                "COMMIT;",
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 5 AND 5;",
+                "COMMIT;",
+            ]
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn wraps_each_batch_with_mssql_transaction_keywords() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            dialect_kind: SqlDialectKind::MsSql,
+            transaction_mode: TransactionMode::PerBatch,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(content.matches("BEGIN TRANSACTION;").count(), 2);
+        assert_eq!(content.matches("COMMIT;").count(), 2);
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn renders_half_open_batches_that_share_boundaries() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 5,
+            batch_size: 2,
+            range_bounds: RangeBounds::HalfOpen,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 3);
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec![
+                "DELETE FROM users WHERE id >= 1 AND id < 3;",
+                "DELETE FROM users WHERE id >= 3 AND id < 5;",
+                "DELETE FROM users WHERE id >= 5 AND id < 6;",
+            ]
+        );
 
         fs::remove_file(output_path).expect("temp output file should be removed");
     }
+
+    #[test]
+    fn overlapping_batches_share_ids_with_the_next_batch() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 10,
+            batch_size: 4,
+            overlap: 2,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec![
+                "DELETE FROM users WHERE id BETWEEN 1 AND 6;",
+                "DELETE FROM users WHERE id BETWEEN 5 AND 10;",
+                "DELETE FROM users WHERE id BETWEEN 9 AND 10;",
+            ]
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn single_id_range_yields_one_batch() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            start_id: 100,
+            end_id: 100,
+            batch_size: 50,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 1);
+        assert_eq!(
+            fs::read_to_string(&output_path).expect("output should be readable"),
+            "DELETE FROM users WHERE id BETWEEN 100 AND 100;\n"
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn empty_id_list_fails_without_creating_output() {
+        let output_path = build_temp_output_path();
+        for dry_run in [false, true] {
+            let command = GenerateBatchedSqlCommand {
+                id_list: Some(Vec::new()),
+                dry_run,
+                ..build_delete_command(output_path.clone())
+            };
+
+            let error = GenerateBatchedSqlUseCase
+                .execute(command)
+                .expect_err("empty id list should be rejected");
+
+            assert!(matches!(
+                error,
+                GenerateBatchedSqlError::Domain(DomainError::NoBatches)
+            ));
+        }
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn renders_in_list_batches_from_explicit_ids() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            batch_size: 2,
+            id_list: Some(vec![30, 10, 20, 10, 40]),
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 2);
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec![
+                "DELETE FROM users WHERE id IN (10, 20);",
+                "DELETE FROM users WHERE id IN (30, 40);",
+            ]
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn renders_quoted_in_list_batches_from_string_ids() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            batch_size: 2,
+            string_id_list: Some(["c3", "a1", "b2"].map(String::from).to_vec()),
+            annotate: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 2);
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec![
+                "-- batch 1/2 | ids a1..b2 (2 values)",
+                "DELETE FROM users WHERE id IN ('a1', 'b2');",
+                "-- batch 2/2 | ids c3..c3 (1 values)",
+                "DELETE FROM users WHERE id IN ('c3');",
+            ]
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn splits_batches_across_zero_padded_files() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 5,
+            sleep_seconds: 1,
+            transaction_mode: TransactionMode::PerBatch,
+            split_every: Some(2),
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 5);
+        assert_eq!(
+            result.files_written,
+            vec![
+                build_split_output_path(&output_path, 1, 4),
+                build_split_output_path(&output_path, 2, 4),
+                build_split_output_path(&output_path, 3, 4),
+            ]
+        );
+        assert!(
+            result.files_written[0]
+                .to_string_lossy()
+                .ends_with(".0001.sql")
+        );
+        assert!(!output_path.exists());
+
+        let first_file =
+            fs::read_to_string(&result.files_written[0]).expect("output should be readable");
+        assert_eq!(
+            first_file.lines().collect::<Vec<_>>(),
+            vec![
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;",
+                "COMMIT;",
+                "DO SLEEP(1);",
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;",
+                "COMMIT;",
+            ]
+        );
+        let last_file =
+            fs::read_to_string(&result.files_written[2]).expect("output should be readable");
+        assert_eq!(
+            last_file.lines().collect::<Vec<_>>(),
+            vec![
+                "BEGIN;",
+                "DELETE FROM users WHERE id BETWEEN 5 AND 5;",
+                "COMMIT;",
+            ]
+        );
+
+        for file in result.files_written {
+            fs::remove_file(file).expect("temp output file should be removed");
+        }
+    }
+
+    #[test]
+    fn stops_after_batch_limit_and_reports_the_full_count() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 5,
+            batch_limit: Some(2),
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!((result.batch_count, result.total_batch_count), (2, 5));
+        let output = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            vec![
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;",
+            ]
+        );
+
+        let dry_run_result = GenerateBatchedSqlUseCase
+            .execute(GenerateBatchedSqlCommand {
+                end_id: 5,
+                batch_limit: Some(2),
+                dry_run: true,
+                ..build_delete_command(output_path.clone())
+            })
+            .expect("dry run should succeed");
+        assert_eq!(
+            (dry_run_result.batch_count, dry_run_result.total_batch_count),
+            (2, 5)
+        );
+        assert_eq!(
+            dry_run_result
+                .dry_run_preview
+                .expect("dry run should have a preview")
+                .last_batch_sql,
+            "DELETE FROM users WHERE id BETWEEN 2 AND 2"
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn rejects_zero_batch_limit() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            batch_limit: Some(0),
+            ..build_delete_command(output_path.clone())
+        };
+
+        let error = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect_err("zero batch limit should be rejected");
+
+        assert!(matches!(error, GenerateBatchedSqlError::InvalidCommand(_)));
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn keeps_single_file_when_split_is_disabled() {
+        let output_path = build_temp_output_path();
+        let command = build_delete_command(output_path.clone());
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.files_written, vec![output_path.clone()]);
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn split_output_path_keeps_directory_and_extension() {
+        assert_eq!(
+            build_split_output_path(&PathBuf::from("out/id_slice.sql"), 12, 4),
+            PathBuf::from("out/id_slice.0012.sql")
+        );
+        assert_eq!(
+            build_split_output_path(&PathBuf::from("batches"), 3, 5),
+            PathBuf::from("batches.00003")
+        );
+        assert_eq!(
+            build_split_output_path(&PathBuf::from("out/id_slice.sql.gz"), 2, 4),
+            PathBuf::from("out/id_slice.0002.sql.gz")
+        );
+    }
+
+    #[test]
+    fn renders_batches_in_memory_without_writing_files() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 3,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let rendered = GenerateBatchedSqlUseCase
+            .render_batches(&command)
+            .expect("batches should be prepared")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("batches should be rendered");
+
+        assert_eq!(
+            rendered,
+            vec![
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2",
+                "DELETE FROM users WHERE id BETWEEN 3 AND 3",
+            ]
+        );
+        assert!(!output_path.exists());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_rendering_preserves_ascending_order() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: (super::PARALLEL_RENDER_CHUNK_SIZE * 2 + 7) as i128,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(content.lines().count(), result.batch_count);
+        for (index, line) in content.lines().enumerate() {
+            let id = index + 1;
+            assert_eq!(
+                line,
+                format!("DELETE FROM users WHERE id BETWEEN {id} AND {id};")
+            );
+        }
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn ends_statements_with_configured_terminator() {
+        let output_path = build_temp_output_path();
+        let go_command = GenerateBatchedSqlCommand {
+            raw_sql: "DELETE FROM users;".to_string(),
+            dialect_kind: SqlDialectKind::MsSql,
+            statement_terminator: StatementTerminator::Go,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(go_command)
+            .expect("use case should run");
+        let go_content = fs::read_to_string(&output_path).expect("output should be readable");
+        GenerateBatchedSqlUseCase
+            .execute(GenerateBatchedSqlCommand {
+                statement_terminator: StatementTerminator::Custom(";\n".to_string()),
+                ..build_delete_command(output_path.clone())
+            })
+            .expect("use case should run");
+        let custom_content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(
+            go_content,
+            concat!(
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1\nGO\n",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2\nGO\n",
+            )
+        );
+        assert_eq!(
+            custom_content,
+            concat!(
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;\n\n",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;\n\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn emits_batches_newest_first_in_descending_order() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 105,
+            batch_size: 50,
+            descending: true,
+            annotate: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(result.batch_count, 3);
+        assert_eq!(
+            content,
+            concat!(
+                "-- batch 1/3 | id 101..105\n",
+                "DELETE FROM users WHERE id BETWEEN 101 AND 105;\n",
+                "-- batch 2/3 | id 51..100\n",
+                "DELETE FROM users WHERE id BETWEEN 51 AND 100;\n",
+                "-- batch 3/3 | id 1..50\n",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 50;\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn validates_first_and_last_batch_unless_all_are_requested() {
+        assert!(!should_validate_batch(BatchValidation::Off, 0, true));
+        assert!(should_validate_batch(
+            BatchValidation::FirstAndLast,
+            0,
+            false
+        ));
+        assert!(!should_validate_batch(
+            BatchValidation::FirstAndLast,
+            1,
+            false
+        ));
+        assert!(should_validate_batch(
+            BatchValidation::FirstAndLast,
+            2,
+            true
+        ));
+        assert!(should_validate_batch(BatchValidation::All, 1, false));
+    }
+
+    #[test]
+    fn points_at_the_batch_that_fails_to_reparse() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            validation: BatchValidation::All,
+            ..build_delete_command(output_path.clone())
+        };
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("valid batches should pass validation");
+        let sql_template = parse_batch_template(&build_delete_command(output_path.clone()))
+            .expect("template should be parsed");
+
+        assert!(matches!(
+            validate_rendered_batch(&sql_template, 4, "DELETE FROM users WHERE"),
+            Err(DomainError::InvalidRenderedBatch {
+                batch_number: 5,
+                ..
+            })
+        ));
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn emits_one_statement_per_shard() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            shard_count: Some(3),
+            annotate: true,
+            dialect_kind: SqlDialectKind::MySql,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(result.batch_count, 3);
+        assert_eq!(
+            content,
+            concat!(
+                "-- batch 1/3 | shard 0 of 3\n",
+                "DELETE FROM users WHERE id % 3 = 0;\n",
+                "-- batch 2/3 | shard 1 of 3\n",
+                "DELETE FROM users WHERE id % 3 = 1;\n",
+                "-- batch 3/3 | shard 2 of 3\n",
+                "DELETE FROM users WHERE id % 3 = 2;\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn emits_half_open_date_batches() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            range_column: "created_at".to_string(),
+            date_range: Some(DateRange {
+                start_date: "2023-01-01".parse().expect("date should parse"),
+                end_date: "2023-03-15".parse().expect("date should parse"),
+                step: DateStep::Months(1),
+            }),
+            annotate: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(result.batch_count, 3);
+        assert_eq!(
+            content,
+            concat!(
+                "-- batch 1/3 | date 2023-01-01..<2023-02-01\n",
+                "DELETE FROM users WHERE created_at >= '2023-01-01' AND created_at < '2023-02-01';\n",
+                "-- batch 2/3 | date 2023-02-01..<2023-03-01\n",
+                "DELETE FROM users WHERE created_at >= '2023-02-01' AND created_at < '2023-03-01';\n",
+                "-- batch 3/3 | date 2023-03-01..<2023-03-15\n",
+                "DELETE FROM users WHERE created_at >= '2023-03-01' AND created_at < '2023-03-15';\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn strict_id_column_rejects_sql_with_manual_range() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            raw_sql: "DELETE FROM users WHERE id BETWEEN 1 AND 1000".to_string(),
+            strict_id_column: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let error = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect_err("manual primary key range should be rejected");
+
+        assert!(matches!(
+            error,
+            GenerateBatchedSqlError::Domain(DomainError::ConstrainedIdColumn(predicate))
+                if predicate == "id BETWEEN 1 AND 1000"
+        ));
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn rejects_shards_with_limit_based_batching() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            shard_count: Some(3),
+            batching_mode: BatchingMode::LimitBased,
+            ..build_delete_command(output_path.clone())
+        };
+
+        assert!(GenerateBatchedSqlUseCase.execute(command).is_err());
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn emits_single_limited_statement_in_limit_based_mode() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 1_000_000,
+            batch_size: 1_000,
+            sleep_seconds: 1,
+            raw_sql: "DELETE FROM users WHERE status = 'old'".to_string(),
+            batching_mode: BatchingMode::LimitBased,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        assert_eq!(result.batch_count, 1);
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert_eq!(
+            content,
+            "DELETE FROM users WHERE status = 'old' ORDER BY id LIMIT 1000;\n"
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn annotates_each_batch_with_position_and_range() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 105,
+            batch_size: 50,
+            annotate: true,
+            transaction_mode: TransactionMode::PerBatch,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[..3],
+            [
+                "BEGIN;",
+                "-- batch 1/3 | id 1..50",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 50;",
+            ]
+        );
+        assert!(content.contains("-- batch 3/3 | id 101..105\n"));
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn dry_run_renders_first_and_last_batch_without_writing() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 1_000_000_000_000,
+            batch_size: 1_000,
+            dry_run: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("dry run should succeed");
+        let preview = result.dry_run_preview.expect("preview should be set");
+
+        assert!(!output_path.exists());
+        assert_eq!(result.output_path, None);
+        assert!(result.files_written.is_empty());
+        assert_eq!(result.batch_count, 1_000_000_000);
+        assert_eq!(
+            preview.first_batch_sql,
+            "DELETE FROM users WHERE id BETWEEN 1 AND 1000"
+        );
+        assert_eq!(
+            preview.last_batch_sql,
+            "DELETE FROM users WHERE id BETWEEN 999999999001 AND 1000000000000"
+        );
+    }
+
+    #[test]
+    fn rejects_split_files_when_writing_to_stdout() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            write_to_stdout: true,
+            split_every: Some(1),
+            ..build_delete_command(output_path.clone())
+        };
+
+        let error = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect_err("split stdout output should be rejected");
+        assert!(error.to_string().contains("cannot be combined with stdout"));
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn streams_batches_as_json_array() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 105,
+            batch_size: 50,
+            sleep_seconds: 1,
+            raw_sql: "DELETE FROM users WHERE name = 'a\"b'".to_string(),
+            output_format: OutputFormat::Json,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(result.batch_count, 3);
+        assert_eq!(
+            content,
+            concat!(
+                "[\n",
+                r#"{"index":1,"start_id":1,"end_id":50,"sql":"DELETE FROM users WHERE id BETWEEN 1 AND 50 AND (name = 'a\"b')"},"#,
+                "\n",
+                r#"{"index":2,"start_id":51,"end_id":100,"sql":"DELETE FROM users WHERE id BETWEEN 51 AND 100 AND (name = 'a\"b')"},"#,
+                "\n",
+                r#"{"index":3,"start_id":101,"end_id":105,"sql":"DELETE FROM users WHERE id BETWEEN 101 AND 105 AND (name = 'a\"b')"}"#,
+                "\n]\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn closes_json_array_in_every_split_file() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            end_id: 3,
+            split_every: Some(2),
+            output_format: OutputFormat::Json,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+
+        let batch_counts = result
+            .files_written
+            .iter()
+            .map(|file_path| {
+                let content = fs::read_to_string(file_path).expect("output should be readable");
+                assert!(content.starts_with("[\n") && content.ends_with("\n]\n"));
+                content.matches("\"index\"").count()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(batch_counts, vec![2, 1]);
+
+        for file_path in result.files_written {
+            fs::remove_file(file_path).expect("temp output file should be removed");
+        }
+    }
+
+    #[test]
+    fn writes_commented_verification_before_each_destructive_batch() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            annotate: true,
+            with_verify: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(
+            content,
+            concat!(
+                "-- batch 1/2 | id 1..1\n",
+                "-- verify: SELECT COUNT(*) FROM users WHERE id BETWEEN 1 AND 1;\n",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;\n",
+                "-- batch 2/2 | id 2..2\n",
+                "-- verify: SELECT COUNT(*) FROM users WHERE id BETWEEN 2 AND 2;\n",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[test]
+    fn appends_batches_after_existing_content() {
+        let output_path = build_temp_output_path();
+        fs::write(&output_path, "-- earlier run").expect("existing output should be written");
+        let command = GenerateBatchedSqlCommand {
+            append: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        GenerateBatchedSqlUseCase
+            .execute(GenerateBatchedSqlCommand {
+                start_id: 3,
+                end_id: 3,
+                append: true,
+                ..build_delete_command(output_path.clone())
+            })
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+
+        assert_eq!(result.batch_count, 2);
+        assert_eq!(
+            content,
+            concat!(
+                "-- earlier run\n",
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;\n",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;\n",
+                "DELETE FROM users WHERE id BETWEEN 3 AND 3;\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compresses_output_when_path_ends_in_gz() {
+        use flate2::read::GzDecoder;
+
+        let output_path = build_temp_output_path().with_extension("sql.gz");
+        let command = build_delete_command(output_path.clone());
+
+        let result = GenerateBatchedSqlUseCase
+            .execute(command)
+            .expect("use case should run");
+        let mut content = String::new();
+        GzDecoder::new(File::open(&output_path).expect("gzip output should exist"))
+            .read_to_string(&mut content)
+            .expect("output should be valid gzip");
+
+        assert_eq!(result.files_written, vec![output_path.clone()]);
+        assert_eq!(
+            content,
+            concat!(
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1;\n",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2;\n",
+            )
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn rejects_gz_output_without_gzip_feature() {
+        let output_path = build_temp_output_path().with_extension("sql.gz");
+
+        let error = GenerateBatchedSqlUseCase
+            .execute(build_delete_command(output_path.clone()))
+            .expect_err("gz output should require the gzip feature");
+
+        assert!(matches!(error, GenerateBatchedSqlError::InvalidCommand(_)));
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn dry_run_prints_missing_where_warning() {
+        let output_path = build_temp_output_path();
+        let command = GenerateBatchedSqlCommand {
+            dry_run: true,
+            ..build_delete_command(output_path.clone())
+        };
+        let mut warnings = Vec::new();
+
+        GenerateBatchedSqlUseCase
+            .execute_with_warnings_to(command, &mut warnings)
+            .expect("dry run should succeed");
+
+        let warnings = String::from_utf8(warnings).expect("warnings should be utf8");
+        assert!(
+            warnings.contains("input SQL has no WHERE clause"),
+            "{warnings}"
+        );
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn labels_verification_of_joined_statement_as_approximate() {
+        let output_path = build_temp_output_path();
+        let build_command = || GenerateBatchedSqlCommand {
+            end_id: 1,
+            raw_sql: "DELETE u FROM users u JOIN orders o ON o.user_id = u.id WHERE o.state = 'x'"
+                .to_string(),
+            with_verify: true,
+            ..build_delete_command(output_path.clone())
+        };
+
+        GenerateBatchedSqlUseCase
+            .execute(build_command())
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert!(
+            content.starts_with(
+                "-- verify (approximate, conditions on joined tables dropped): SELECT COUNT(*) FROM users u WHERE u.id BETWEEN 1 AND 1;\n"
+            ),
+            "{content}"
+        );
+
+        GenerateBatchedSqlUseCase
+            .execute(GenerateBatchedSqlCommand {
+                output_format: OutputFormat::Json,
+                ..build_command()
+            })
+            .expect("use case should run");
+        let content = fs::read_to_string(&output_path).expect("output should be readable");
+        assert!(
+            content.contains(r#""verify_approximate":true"#),
+            "{content}"
+        );
+
+        fs::remove_file(output_path).expect("temp output file should be removed");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn renders_small_runs_serially() {
+        assert!(!super::renders_in_parallel(3));
+        assert!(!super::renders_in_parallel(
+            super::PARALLEL_RENDER_THRESHOLD - 1
+        ));
+        assert!(super::renders_in_parallel(super::PARALLEL_RENDER_THRESHOLD));
+
+        let command = GenerateBatchedSqlCommand {
+            end_id: 3,
+            ..build_delete_command(build_temp_output_path())
+        };
+        let rendered_sql = GenerateBatchedSqlUseCase
+            .render_batches(&command)
+            .expect("batches should render")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("every batch should render");
+        assert_eq!(
+            rendered_sql,
+            vec![
+                "DELETE FROM users WHERE id BETWEEN 1 AND 1",
+                "DELETE FROM users WHERE id BETWEEN 2 AND 2",
+                "DELETE FROM users WHERE id BETWEEN 3 AND 3",
+            ]
+        );
+    }
 }
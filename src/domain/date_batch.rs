@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use chrono::{Days, Months, NaiveDate};
+
+use crate::domain::error::DomainError;
+
+/// Width of one date batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStep {
+    Days(u32),
+    Months(u32),
+}
+
+/// Half-open date span `start_date..end_date` to slice by `step`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub step: DateStep,
+}
+
+/// One batch, matched as `column >= start_date AND column < end_date`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateBatchRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Walks a date span the way `IdBatchSlicer` walks ids; each batch ends where the next starts,
+/// so no boundary day is covered twice.
+#[derive(Debug)]
+pub struct DateBatchSlicer {
+    date_range: DateRange,
+}
+
+impl DateBatchSlicer {
+    pub fn new(date_range: DateRange) -> Result<Self, DomainError> {
+        if date_range.start_date >= date_range.end_date {
+            return Err(DomainError::InvalidDateRange {
+                start_date: date_range.start_date,
+                end_date: date_range.end_date,
+            });
+        }
+        if matches!(date_range.step, DateStep::Days(0) | DateStep::Months(0)) {
+            return Err(DomainError::InvalidDateStep);
+        }
+
+        Ok(Self { date_range })
+    }
+
+    pub fn count_batches(&self) -> usize {
+        self.iter_ranges().count()
+    }
+
+    pub fn iter_ranges(&self) -> impl Iterator<Item = DateBatchRange> + use<> {
+        let DateRange {
+            start_date,
+            end_date,
+            step,
+        } = self.date_range;
+        // Each boundary is offset from `start_date`, so month steps from the 31st do not drift
+        // to the 28th after February.
+        let boundary = move |batch_index: u32| match step {
+            DateStep::Days(days) => batch_index
+                .checked_mul(days)
+                .and_then(|offset| start_date.checked_add_days(Days::new(offset.into()))),
+            DateStep::Months(months) => batch_index
+                .checked_mul(months)
+                .and_then(|offset| start_date.checked_add_months(Months::new(offset))),
+        };
+        (0..u32::MAX).map_while(move |batch_index| {
+            let batch_start =
+                boundary(batch_index).filter(|batch_start| *batch_start < end_date)?;
+            let batch_end = boundary(batch_index + 1).map_or(end_date, |next| next.min(end_date));
+            Some(DateBatchRange {
+                start_date: batch_start,
+                end_date: batch_end,
+            })
+        })
+    }
+}
+
+impl std::fmt::Display for DateStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateStep::Days(days) => write!(f, "{days}d"),
+            DateStep::Months(months) => write!(f, "{months}M"),
+        }
+    }
+}
+
+impl FromStr for DateStep {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        // `<n>d` days, `<n>w` weeks, `<n>M` months or `<n>y` years; `m` is rejected as ambiguous.
+        let trimmed_value = value.trim();
+        let unit_start = trimmed_value
+            .find(|character: char| !character.is_ascii_digit())
+            .unwrap_or(trimmed_value.len());
+        let (count, unit) = trimmed_value.split_at(unit_start);
+        let count = count
+            .parse::<u32>()
+            .map_err(|_| anyhow!("Date step must start with a number: {value}"))?;
+        let overflow = || anyhow!("Date step is too large: {value}");
+        match unit {
+            "d" => Ok(DateStep::Days(count)),
+            "w" => Ok(DateStep::Days(count.checked_mul(7).ok_or_else(overflow)?)),
+            "M" => Ok(DateStep::Months(count)),
+            "y" => Ok(DateStep::Months(
+                count.checked_mul(12).ok_or_else(overflow)?,
+            )),
+            _ => Err(anyhow!(
+                "Unsupported date step unit in {value}. Available units: d (days), w (weeks), M (months), y (years)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{DateBatchRange, DateBatchSlicer, DateRange, DateStep};
+    use crate::domain::error::DomainError;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().expect("test date should parse")
+    }
+
+    fn slice(start_date: &str, end_date: &str, step: DateStep) -> Vec<(String, String)> {
+        DateBatchSlicer::new(DateRange {
+            start_date: date(start_date),
+            end_date: date(end_date),
+            step,
+        })
+        .expect("slicer should be created")
+        .iter_ranges()
+        .map(|range| (range.start_date.to_string(), range.end_date.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn monthly_batches_are_half_open_and_cap_the_last_batch() {
+        assert_eq!(
+            slice("2023-01-01", "2023-03-15", DateStep::Months(1)),
+            vec![
+                ("2023-01-01".to_string(), "2023-02-01".to_string()),
+                ("2023-02-01".to_string(), "2023-03-01".to_string()),
+                ("2023-03-01".to_string(), "2023-03-15".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn month_steps_from_month_end_do_not_drift() {
+        let ranges = slice("2023-01-31", "2023-04-01", DateStep::Months(1));
+
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|(start_date, _)| start_date.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2023-01-31", "2023-02-28", "2023-03-31"]
+        );
+    }
+
+    #[test]
+    fn daily_batches_cover_every_day_once() {
+        let slicer = DateBatchSlicer::new(DateRange {
+            start_date: date("2024-02-27"),
+            end_date: date("2024-03-02"),
+            step: DateStep::Days(2),
+        })
+        .expect("slicer should be created");
+
+        assert_eq!(slicer.count_batches(), 2);
+        assert_eq!(
+            slicer.iter_ranges().last(),
+            Some(DateBatchRange {
+                start_date: date("2024-02-29"),
+                end_date: date("2024-03-02"),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_empty_range_and_zero_step() {
+        let date_range = DateRange {
+            start_date: date("2023-02-01"),
+            end_date: date("2023-02-01"),
+            step: DateStep::Days(1),
+        };
+        assert_eq!(
+            DateBatchSlicer::new(date_range).err(),
+            Some(DomainError::InvalidDateRange {
+                start_date: date("2023-02-01"),
+                end_date: date("2023-02-01"),
+            })
+        );
+        assert_eq!(
+            DateBatchSlicer::new(DateRange {
+                end_date: date("2023-03-01"),
+                step: DateStep::Months(0),
+                ..date_range
+            })
+            .err(),
+            Some(DomainError::InvalidDateStep)
+        );
+    }
+
+    #[test]
+    fn parses_date_steps() {
+        assert_eq!("1d".parse::<DateStep>().ok(), Some(DateStep::Days(1)));
+        assert_eq!("2w".parse::<DateStep>().ok(), Some(DateStep::Days(14)));
+        assert_eq!("1M".parse::<DateStep>().ok(), Some(DateStep::Months(1)));
+        assert_eq!("1y".parse::<DateStep>().ok(), Some(DateStep::Months(12)));
+        assert!("1m".parse::<DateStep>().is_err());
+        assert!("d".parse::<DateStep>().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::domain::sql_dialect::SqlDialectKind;
+
+/// Why a batch plan could not be built or rendered.
+///
+/// It implements `std::error::Error`, so `?` still converts it into `anyhow::Error`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DomainError {
+    #[error("Input SQL must not be empty")]
+    EmptySql,
+    #[error("Unable to parse SQL with selected dialect: {0}")]
+    ParseFailed(String),
+    #[error("Input SQL must contain exactly one statement, but got {0}")]
+    StatementCount(usize),
+    /// The statement kind or shape cannot be batched in the requested way.
+    #[error("{0}")]
+    UnsupportedStatement(String),
+    #[error("{0}")]
+    InvalidIdColumn(String),
+    #[error("End ID must be greater than or equal to Start ID")]
+    InvalidIdRange { start_id: i128, end_id: i128 },
+    #[error(
+        "Half-open ranges cannot include i128::MAX because the exclusive upper bound would overflow"
+    )]
+    HalfOpenRangeOverflow,
+    #[error("Batch size must be greater than 0")]
+    InvalidBatchSize,
+    #[error("A gap of {gap} ids must be smaller than the batch size {batch_size}")]
+    InvalidBatchGap { gap: u128, batch_size: usize },
+    #[error("The input produces no batches, so nothing was generated")]
+    NoBatches,
+    #[error("ID batch must contain at least one id")]
+    EmptyIdBatch,
+    /// The input `WHERE` already bounds the ranged key column; holds the offending predicate.
+    #[error("Input SQL already constrains the id column: {0}")]
+    ConstrainedIdColumn(String),
+    /// A rendered batch does not parse again; `batch_number` counts from 1.
+    #[error("Batch {batch_number} does not parse with the selected dialect: {reason}")]
+    InvalidRenderedBatch { batch_number: usize, reason: String },
+    #[error("End date {end_date} must be after start date {start_date}")]
+    InvalidDateRange {
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    },
+    #[error("Date step must be greater than 0")]
+    InvalidDateStep,
+    #[error("Shard count must be greater than 0")]
+    InvalidShardCount,
+    /// An id the dialect cannot write as an exact integer literal.
+    #[error("Id {id} cannot be written exactly on {dialect_kind}: {reason}")]
+    UnrepresentableId {
+        id: i128,
+        dialect_kind: SqlDialectKind,
+        reason: &'static str,
+    },
+}
@@ -1,52 +1,302 @@
-use anyhow::{Result, anyhow};
+use crate::domain::error::DomainError;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct IdBatchRange {
     pub start_id: i128,
     pub end_id: i128,
 }
 
+/// How a batch's `start_id`/`end_id` pair is meant to be compared against the primary key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RangeBounds {
+    /// `pk BETWEEN start_id AND end_id`, both ends included.
+    #[default]
+    InclusiveBetween,
+    /// `pk >= start_id AND pk < end_id`; each batch ends where the next one starts.
+    HalfOpen,
+}
+
+/// Whether batches are cut by id ranges or by repeatedly running one `ORDER BY ... LIMIT` statement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchingMode {
+    /// One statement per id range produced by [`IdBatchSlicer`].
+    #[default]
+    RangeBased,
+    /// A single statement with `ORDER BY pk LIMIT batch_size`, re-run until it affects zero rows.
+    LimitBased,
+}
+
 #[derive(Debug)]
 pub struct IdBatchSlicer {
     start_id: i128,
     end_id: i128,
     batch_size: usize,
+    range_bounds: RangeBounds,
+    descending: bool,
+    overlap: i128,
 }
 
 impl IdBatchSlicer {
-    pub fn new(start_id: i128, end_id: i128, batch_size: usize) -> Result<Self> {
+    pub fn new(
+        start_id: i128,
+        end_id: i128,
+        batch_size: usize,
+        range_bounds: RangeBounds,
+    ) -> Result<Self, DomainError> {
         if start_id > end_id {
-            return Err(anyhow!("End ID must be greater than or equal to Start ID"));
+            return Err(DomainError::InvalidIdRange { start_id, end_id });
         }
         if batch_size == 0 {
-            return Err(anyhow!("Batch size must be greater than 0"));
+            return Err(DomainError::InvalidBatchSize);
+        }
+        if range_bounds == RangeBounds::HalfOpen && end_id == i128::MAX {
+            return Err(DomainError::HalfOpenRangeOverflow);
         }
 
         Ok(Self {
             start_id,
             end_id,
             batch_size,
+            range_bounds,
+            descending: false,
+            overlap: 0,
         })
     }
 
-    pub fn iter_ranges(&self) -> impl Iterator<Item = IdBatchRange> + '_ {
-        let batch_size_as_i128 = self.batch_size as i128;
-        (self.start_id..=self.end_id)
-            .step_by(self.batch_size)
-            .map(move |current_start| IdBatchRange {
-                start_id: current_start,
-                end_id: (current_start + batch_size_as_i128 - 1).min(self.end_id),
-            })
+    /// Ends each range `overlap` ids past its grid boundary, or that many ids short of it when
+    /// negative; starts stay on the grid and no range runs past `end_id`.
+    pub fn with_overlap(mut self, overlap: i128) -> Result<Self, DomainError> {
+        // A gap of a whole batch would leave ranges that end before they start.
+        if overlap < 0 && overlap.unsigned_abs() >= self.batch_size as u128 {
+            return Err(DomainError::InvalidBatchGap {
+                gap: overlap.unsigned_abs(),
+                batch_size: self.batch_size,
+            });
+        }
+        self.overlap = overlap;
+        Ok(self)
+    }
+
+    /// Yields the same ranges from the highest ids down; each range still has `start <= end`.
+    pub fn in_descending_order(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn start_id(&self) -> i128 {
+        self.start_id
+    }
+
+    pub fn end_id(&self) -> i128 {
+        self.end_id
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn range_bounds(&self) -> RangeBounds {
+        self.range_bounds
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.descending
+    }
+
+    pub fn overlap(&self) -> i128 {
+        self.overlap
+    }
+
+    /// Number of batches `iter_ranges` yields, computed without walking the ranges.
+    pub fn count_batches(&self) -> usize {
+        count_id_range_batches(self.start_id, self.end_id, self.batch_size)
+    }
+
+    /// Yields the ranges without borrowing the slicer, so callers can keep the iterator around.
+    pub fn iter_ranges(&self) -> Box<dyn Iterator<Item = IdBatchRange>> {
+        let (start_id, end_id) = (self.start_id, self.end_id);
+        let (range_bounds, batch_size, overlap) =
+            (self.range_bounds, self.batch_size, self.overlap);
+        let to_range = move |current_start| {
+            build_range(current_start, end_id, batch_size, range_bounds, overlap)
+        };
+        if !self.descending {
+            return Box::new((start_id..=end_id).step_by(batch_size).map(to_range));
+        }
+
+        // Stepping down from the highest start keeps every start on the ascending grid.
+        let highest_start = self.highest_range_start();
+        let batch_size_as_i128 = batch_size as i128;
+        let range_starts = std::iter::successors(Some(highest_start), move |current_start| {
+            current_start
+                .checked_sub(batch_size_as_i128)
+                .filter(|previous_start| *previous_start >= start_id)
+        });
+        Box::new(range_starts.map(to_range))
+    }
+
+    /// The range `iter_ranges` ends with, computed without walking the ranges.
+    pub fn last_range(&self) -> IdBatchRange {
+        let last_start = if self.descending {
+            self.start_id
+        } else {
+            self.highest_range_start()
+        };
+        build_range(
+            last_start,
+            self.end_id,
+            self.batch_size,
+            self.range_bounds,
+            self.overlap,
+        )
+    }
+
+    fn highest_range_start(&self) -> i128 {
+        let batch_size = self.batch_size as u128;
+        let highest_start_offset = self.end_id.abs_diff(self.start_id) / batch_size * batch_size;
+        // The highest start never passes `end_id`, so adding its offset cannot overflow.
+        self.start_id.wrapping_add_unsigned(highest_start_offset)
+    }
+}
+
+/// Batches needed to cover `start_id..=end_id`, the ceiling of the id count over `batch_size`.
+/// Returns 0 for a reversed range or a zero batch size, which `IdBatchSlicer::new` rejects.
+pub fn count_id_range_batches(start_id: i128, end_id: i128, batch_size: usize) -> usize {
+    if start_id > end_id || batch_size == 0 {
+        return 0;
+    }
+    let last_offset = end_id.abs_diff(start_id);
+    let batch_count = (last_offset / batch_size as u128).saturating_add(1);
+    usize::try_from(batch_count).unwrap_or(usize::MAX)
+}
+
+fn build_range(
+    current_start: i128,
+    end_id: i128,
+    batch_size: usize,
+    range_bounds: RangeBounds,
+    overlap: i128,
+) -> IdBatchRange {
+    let batch_size_as_i128 = batch_size as i128;
+    match range_bounds {
+        RangeBounds::InclusiveBetween => IdBatchRange {
+            start_id: current_start,
+            end_id: current_start
+                .saturating_add(batch_size_as_i128 - 1)
+                .saturating_add(overlap)
+                .min(end_id),
+        },
+        // `IdBatchSlicer::new` rejects `end_id == i128::MAX` here, so `end_id + 1` cannot overflow.
+        RangeBounds::HalfOpen => IdBatchRange {
+            start_id: current_start,
+            end_id: current_start
+                .saturating_add(batch_size_as_i128)
+                .saturating_add(overlap)
+                .min(end_id + 1),
+        },
+    }
+}
+
+/// Batches an explicit list of ids, for tables whose keys are too sparse for contiguous ranges.
+/// `Id` is `String` for keys such as UUIDs, which are sorted as text.
+#[derive(Debug)]
+pub struct IdListSlicer<Id = i128> {
+    ids: Vec<Id>,
+    batch_size: usize,
+    descending: bool,
+}
+
+impl<Id: Ord + Clone + 'static> IdListSlicer<Id> {
+    pub fn new(mut ids: Vec<Id>, batch_size: usize) -> Result<Self, DomainError> {
+        if batch_size == 0 {
+            return Err(DomainError::InvalidBatchSize);
+        }
+
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(Self {
+            ids,
+            batch_size,
+            descending: false,
+        })
+    }
+
+    /// Yields the same batches from the highest ids down.
+    pub fn in_descending_order(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn count_batches(&self) -> usize {
+        self.ids.len().div_ceil(self.batch_size)
+    }
+
+    pub fn into_batches(self) -> Box<dyn Iterator<Item = Vec<Id>>> {
+        let batch_size = self.batch_size;
+        if self.descending {
+            // Batches are cut from the lowest id as in ascending order, so only their order flips.
+            let batch_starts = (0..self.ids.len()).step_by(batch_size).rev();
+            let ids = self.ids;
+            return Box::new(batch_starts.map(move |batch_start| {
+                ids[batch_start..(batch_start + batch_size).min(ids.len())].to_vec()
+            }));
+        }
+
+        let mut remaining_ids = self.ids.into_iter();
+        Box::new(std::iter::from_fn(move || {
+            let batch = remaining_ids.by_ref().take(batch_size).collect::<Vec<_>>();
+            (!batch.is_empty()).then_some(batch)
+        }))
+    }
+}
+
+/// One residue class of `pk % count`, for workers that each own `pk % count = index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdShard {
+    pub index: usize,
+    pub count: usize,
+}
+
+/// Yields one shard per residue instead of contiguous ranges.
+#[derive(Debug)]
+pub struct ShardSlicer {
+    shard_count: usize,
+}
+
+impl ShardSlicer {
+    pub fn new(shard_count: usize) -> Result<Self, DomainError> {
+        if shard_count == 0 {
+            return Err(DomainError::InvalidShardCount);
+        }
+
+        Ok(Self { shard_count })
+    }
+
+    pub fn count_batches(&self) -> usize {
+        self.shard_count
+    }
+
+    pub fn iter_shards(&self) -> impl Iterator<Item = IdShard> + use<> {
+        let shard_count = self.shard_count;
+        (0..shard_count).map(move |index| IdShard {
+            index,
+            count: shard_count,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::IdBatchSlicer;
+    use super::{
+        IdBatchRange, IdBatchSlicer, IdListSlicer, IdShard, RangeBounds, ShardSlicer,
+        count_id_range_batches,
+    };
+    use crate::domain::error::DomainError;
 
     #[test]
     fn iter_ranges_caps_last_batch() {
-        let slicer = IdBatchSlicer::new(1, 105, 50).expect("slicer should be created");
+        let slicer = IdBatchSlicer::new(1, 105, 50, RangeBounds::InclusiveBetween)
+            .expect("slicer should be created");
         let ranges = slicer
             .iter_ranges()
             .map(|range| (range.start_id, range.end_id))
@@ -54,4 +304,283 @@ mod tests {
 
         assert_eq!(ranges, vec![(1, 50), (51, 100), (101, 105)]);
     }
+
+    #[test]
+    fn half_open_ranges_start_where_previous_batch_ended() {
+        let slicer = IdBatchSlicer::new(1, 105, 50, RangeBounds::HalfOpen)
+            .expect("slicer should be created");
+        let ranges = slicer
+            .iter_ranges()
+            .map(|range| (range.start_id, range.end_id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(1, 51), (51, 101), (101, 106)]);
+    }
+
+    #[test]
+    fn overlap_extends_ranges_but_never_past_end_id() {
+        let slicer = IdBatchSlicer::new(1, 105, 50, RangeBounds::InclusiveBetween)
+            .and_then(|slicer| slicer.with_overlap(5))
+            .expect("slicer should be created");
+        let ranges = slicer
+            .iter_ranges()
+            .map(|range| (range.start_id, range.end_id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(1, 55), (51, 105), (101, 105)]);
+        assert!(slicer.iter_ranges().all(|range| range.end_id <= 105));
+        assert_eq!(
+            slicer.last_range(),
+            IdBatchRange {
+                start_id: 101,
+                end_id: 105,
+            }
+        );
+    }
+
+    #[test]
+    fn negative_overlap_leaves_gaps_between_ranges() {
+        let slicer = IdBatchSlicer::new(1, 100, 50, RangeBounds::HalfOpen)
+            .and_then(|slicer| slicer.with_overlap(-10))
+            .expect("slicer should be created");
+        let ranges = slicer
+            .iter_ranges()
+            .map(|range| (range.start_id, range.end_id))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(1, 41), (51, 91)]);
+        assert_eq!(
+            IdBatchSlicer::new(1, 100, 50, RangeBounds::HalfOpen)
+                .and_then(|slicer| slicer.with_overlap(-50))
+                .err(),
+            Some(DomainError::InvalidBatchGap {
+                gap: 50,
+                batch_size: 50,
+            })
+        );
+    }
+
+    #[test]
+    fn every_id_is_covered_exactly_once_for_both_range_bounds() {
+        for range_bounds in [RangeBounds::InclusiveBetween, RangeBounds::HalfOpen] {
+            let slicer =
+                IdBatchSlicer::new(-7, 23, 4, range_bounds).expect("slicer should be created");
+            let ranges = slicer.iter_ranges().collect::<Vec<_>>();
+
+            for id in -9..=25 {
+                let covering_batches = ranges
+                    .iter()
+                    .filter(|range| match range_bounds {
+                        RangeBounds::InclusiveBetween => range.start_id <= id && id <= range.end_id,
+                        RangeBounds::HalfOpen => range.start_id <= id && id < range.end_id,
+                    })
+                    .count();
+                let expected = usize::from((-7..=23).contains(&id));
+                assert_eq!(covering_batches, expected, "id {id} with {range_bounds:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn caps_last_range_at_end_id_near_i128_max() {
+        let slicer =
+            IdBatchSlicer::new(i128::MAX - 3, i128::MAX, 50, RangeBounds::InclusiveBetween)
+                .expect("slicer should be created");
+        let ranges = slicer
+            .iter_ranges()
+            .map(|range| (range.start_id, range.end_id))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(i128::MAX - 3, i128::MAX)]);
+
+        let slicer = IdBatchSlicer::new(i128::MAX - 3, i128::MAX, 1, RangeBounds::InclusiveBetween)
+            .expect("slicer should be created");
+        let last_range = slicer.iter_ranges().last().expect("range should exist");
+        assert_eq!(
+            (last_range.start_id, last_range.end_id),
+            (i128::MAX, i128::MAX)
+        );
+        assert_eq!(slicer.iter_ranges().count(), 4);
+    }
+
+    #[test]
+    fn counts_range_batches_by_ceil_division() {
+        assert_eq!(count_id_range_batches(1, 42_100, 10), 4_210);
+        assert_eq!(count_id_range_batches(1, 42_101, 10), 4_211);
+        assert_eq!(count_id_range_batches(5, 5, 10), 1);
+        assert_eq!(count_id_range_batches(10, 1, 10), 0);
+        assert_eq!(count_id_range_batches(1, 10, 0), 0);
+        assert_eq!(count_id_range_batches(i128::MIN, i128::MAX, 1), usize::MAX);
+    }
+
+    #[test]
+    fn descending_order_yields_ascending_ranges_in_reverse() {
+        for (start_id, end_id, batch_size) in [(1, 105, 50), (1, 100, 50), (7, 7, 3), (-20, 19, 7)]
+        {
+            for range_bounds in [RangeBounds::InclusiveBetween, RangeBounds::HalfOpen] {
+                let ascending = IdBatchSlicer::new(start_id, end_id, batch_size, range_bounds)
+                    .expect("slicer should be created");
+                let descending = IdBatchSlicer::new(start_id, end_id, batch_size, range_bounds)
+                    .expect("slicer should be created")
+                    .in_descending_order();
+                let to_pairs = |ranges: Box<dyn Iterator<Item = IdBatchRange>>| {
+                    ranges
+                        .map(|range| (range.start_id, range.end_id))
+                        .collect::<Vec<_>>()
+                };
+
+                let mut expected = to_pairs(ascending.iter_ranges());
+                expected.reverse();
+                assert_eq!(to_pairs(descending.iter_ranges()), expected);
+                let last_range = descending.last_range();
+                assert_eq!(
+                    Some(&(last_range.start_id, last_range.end_id)),
+                    expected.last()
+                );
+            }
+        }
+
+        let slicer = IdBatchSlicer::new(1, 105, 50, RangeBounds::InclusiveBetween)
+            .expect("slicer should be created")
+            .in_descending_order();
+        let ranges = slicer
+            .iter_ranges()
+            .map(|range| (range.start_id, range.end_id))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(101, 105), (51, 100), (1, 50)]);
+    }
+
+    #[test]
+    fn descending_id_list_keeps_batch_contents() {
+        let slicer = IdListSlicer::new(vec![5, 1, 4, 2, 3], 2)
+            .expect("slicer should be created")
+            .in_descending_order();
+
+        assert_eq!(
+            slicer.into_batches().collect::<Vec<_>>(),
+            vec![vec![5], vec![3, 4], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn rejects_reversed_range_with_typed_error() {
+        assert_eq!(
+            IdBatchSlicer::new(10, 1, 5, RangeBounds::InclusiveBetween).err(),
+            Some(DomainError::InvalidIdRange {
+                start_id: 10,
+                end_id: 1
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_half_open_range_ending_at_i128_max() {
+        assert_eq!(
+            IdBatchSlicer::new(i128::MAX - 3, i128::MAX, 50, RangeBounds::HalfOpen).err(),
+            Some(DomainError::HalfOpenRangeOverflow)
+        );
+        assert!(
+            IdBatchSlicer::new(i128::MAX - 3, i128::MAX - 1, 50, RangeBounds::HalfOpen).is_ok()
+        );
+    }
+
+    #[test]
+    fn count_batches_matches_iterated_ranges() {
+        for (start_id, end_id, batch_size) in [(1, 105, 50), (1, 100, 50), (7, 7, 3), (-20, 19, 7)]
+        {
+            let slicer = IdBatchSlicer::new(start_id, end_id, batch_size, RangeBounds::HalfOpen)
+                .expect("slicer should be created");
+            assert_eq!(slicer.count_batches(), slicer.iter_ranges().count());
+        }
+
+        let slicer = IdBatchSlicer::new(
+            i128::MIN,
+            i128::MAX,
+            usize::MAX,
+            RangeBounds::InclusiveBetween,
+        )
+        .expect("slicer should be created");
+        assert_eq!(slicer.count_batches(), usize::MAX);
+    }
+
+    #[test]
+    fn last_range_matches_last_iterated_range() {
+        for range_bounds in [RangeBounds::InclusiveBetween, RangeBounds::HalfOpen] {
+            for (start_id, end_id, batch_size) in
+                [(1, 105, 50), (1, 100, 50), (7, 7, 3), (-20, 19, 7)]
+            {
+                let slicer = IdBatchSlicer::new(start_id, end_id, batch_size, range_bounds)
+                    .expect("slicer should be created");
+                let expected = slicer.iter_ranges().last().expect("range should exist");
+                let last_range = slicer.last_range();
+                assert_eq!(
+                    (last_range.start_id, last_range.end_id),
+                    (expected.start_id, expected.end_id)
+                );
+            }
+        }
+
+        let slicer = IdBatchSlicer::new(i128::MIN, i128::MAX, 1, RangeBounds::InclusiveBetween)
+            .expect("slicer should be created");
+        let last_range = slicer.last_range();
+        assert_eq!(
+            (last_range.start_id, last_range.end_id),
+            (i128::MAX, i128::MAX)
+        );
+    }
+
+    #[test]
+    fn id_list_batches_are_sorted_and_deduplicated() {
+        let slicer =
+            IdListSlicer::new(vec![9, 3, 7, 3, 1, 9], 2).expect("slicer should be created");
+
+        assert_eq!(slicer.count_batches(), 2);
+        assert_eq!(
+            slicer.into_batches().collect::<Vec<_>>(),
+            vec![vec![1, 3], vec![7, 9]]
+        );
+    }
+
+    #[test]
+    fn id_list_keeps_partial_last_batch() {
+        let slicer = IdListSlicer::new(vec![5, 1, 4, 2, 3], 2).expect("slicer should be created");
+
+        assert_eq!(slicer.count_batches(), 3);
+        assert_eq!(
+            slicer.into_batches().collect::<Vec<_>>(),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn string_id_list_is_sorted_as_text() {
+        let ids = ["b", "a", "c", "a"].map(String::from).to_vec();
+        let slicer = IdListSlicer::new(ids, 2).expect("slicer should be created");
+
+        assert_eq!(
+            slicer.into_batches().collect::<Vec<_>>(),
+            vec![
+                vec!["a".to_string(), "b".to_string()],
+                vec!["c".to_string()]
+            ]
+        );
+    }
+
+    #[test]
+    fn shard_slicer_yields_every_residue_once() {
+        let slicer = ShardSlicer::new(3).expect("slicer should be created");
+
+        assert_eq!(slicer.count_batches(), 3);
+        assert_eq!(
+            slicer.iter_shards().collect::<Vec<_>>(),
+            vec![
+                IdShard { index: 0, count: 3 },
+                IdShard { index: 1, count: 3 },
+                IdShard { index: 2, count: 3 },
+            ]
+        );
+        assert_eq!(
+            ShardSlicer::new(0).err(),
+            Some(DomainError::InvalidShardCount)
+        );
+    }
 }
@@ -1,2 +1,6 @@
+pub mod date_batch;
+pub mod error;
 pub mod id_batch;
 pub mod sql_dialect;
+pub mod statement_terminator;
+pub mod transaction_mode;
@@ -57,6 +57,94 @@ impl SqlDialectKind {
         }
     }
 
+    /// The pause written between batches: the dialect's sleep statement, or a comment standing
+    /// in for it when the dialect cannot sleep, so the intended pause stays visible.
+    pub fn sleep_statement_or_comment(self, seconds: u64) -> Option<String> {
+        if seconds == 0 {
+            return None;
+        }
+
+        self.sleep_statement(seconds).or_else(|| {
+            Some(self.line_comment(&format!(
+                "sleep {seconds}s between batches (not supported by {self})"
+            )))
+        })
+    }
+
+    /// Single-line comment; `--` is valid in every supported dialect.
+    pub fn line_comment(self, text: &str) -> String {
+        format!("-- {text}")
+    }
+
+    pub fn begin_transaction_statement(self) -> &'static str {
+        match self {
+            SqlDialectKind::MsSql => "BEGIN TRANSACTION;",
+            _ => "BEGIN;",
+        }
+    }
+
+    pub fn commit_transaction_statement(self) -> &'static str {
+        "COMMIT;"
+    }
+
+    /// Quote character for delimited identifiers; `[` renders as `[name]`.
+    pub fn identifier_quote_style(self) -> char {
+        match self {
+            SqlDialectKind::MySql => '`',
+            SqlDialectKind::MsSql => '[',
+            SqlDialectKind::Generic
+            | SqlDialectKind::PostgreSql
+            | SqlDialectKind::Sqlite
+            | SqlDialectKind::Snowflake
+            | SqlDialectKind::DuckDb => '"',
+        }
+    }
+
+    /// Whether `(a, b) >= (1, 2)` row-value comparisons are available.
+    pub fn supports_row_value_comparison(self) -> bool {
+        !matches!(self, SqlDialectKind::MsSql | SqlDialectKind::Snowflake)
+    }
+
+    /// Whether the dialect has a `MERGE INTO ... USING` statement.
+    pub fn supports_merge(self) -> bool {
+        matches!(
+            self,
+            SqlDialectKind::PostgreSql
+                | SqlDialectKind::MsSql
+                | SqlDialectKind::Snowflake
+                | SqlDialectKind::DuckDb
+        )
+    }
+
+    /// Whether modulo is spelled `a % b`; the other dialects get the `MOD(a, b)` function.
+    pub fn supports_modulo_operator(self) -> bool {
+        match self {
+            SqlDialectKind::MySql
+            | SqlDialectKind::PostgreSql
+            | SqlDialectKind::Sqlite
+            | SqlDialectKind::MsSql
+            | SqlDialectKind::DuckDb => true,
+            SqlDialectKind::Generic | SqlDialectKind::Snowflake => false,
+        }
+    }
+
+    /// Whether id bounds outside the 64-bit `BIGINT` range must be cast to `DECIMAL(38,0)`
+    /// instead of written as bare literals.
+    pub fn requires_cast_for_wide_integers(self) -> bool {
+        matches!(self, SqlDialectKind::MsSql)
+    }
+
+    /// Whether integer literals outside the 64-bit range keep their exact value; sqlite reads
+    /// them as lossy `REAL`s.
+    pub fn supports_wide_integers(self) -> bool {
+        !matches!(self, SqlDialectKind::Sqlite)
+    }
+
+    /// Whether `UPDATE`/`DELETE` accept a trailing `ORDER BY ... LIMIT n` out of the box.
+    pub fn supports_order_by_limit_in_dml(self) -> bool {
+        matches!(self, SqlDialectKind::MySql)
+    }
+
     pub fn sleep_unsupported_reason(self) -> Option<&'static str> {
         match self {
             SqlDialectKind::Generic => {
@@ -124,4 +212,55 @@ mod tests {
         assert_eq!(SqlDialectKind::Sqlite.sleep_statement(2), None);
         assert!(SqlDialectKind::Sqlite.sleep_unsupported_reason().is_some());
     }
+
+    #[test]
+    fn sleep_falls_back_to_comment_without_sleep_primitive() {
+        assert_eq!(
+            SqlDialectKind::Sqlite.sleep_statement_or_comment(2),
+            Some("-- sleep 2s between batches (not supported by sqlite)".to_string())
+        );
+        assert_eq!(
+            SqlDialectKind::PostgreSql.sleep_statement_or_comment(2),
+            Some("SELECT pg_sleep(2);".to_string())
+        );
+        assert_eq!(SqlDialectKind::Generic.sleep_statement_or_comment(0), None);
+    }
+
+    #[test]
+    fn modulo_operator_is_limited_to_dialects_that_parse_it() {
+        assert!(SqlDialectKind::MySql.supports_modulo_operator());
+        assert!(SqlDialectKind::MsSql.supports_modulo_operator());
+        assert!(!SqlDialectKind::Generic.supports_modulo_operator());
+        assert!(!SqlDialectKind::Snowflake.supports_modulo_operator());
+    }
+
+    #[test]
+    fn mssql_begins_transaction_with_explicit_keyword() {
+        assert_eq!(
+            SqlDialectKind::MsSql.begin_transaction_statement(),
+            "BEGIN TRANSACTION;"
+        );
+        assert_eq!(
+            SqlDialectKind::MySql.begin_transaction_statement(),
+            "BEGIN;"
+        );
+    }
+
+    #[test]
+    fn only_mssql_casts_wide_integers() {
+        assert_eq!(
+            SqlDialectKind::ALL
+                .into_iter()
+                .filter(|dialect_kind| dialect_kind.requires_cast_for_wide_integers())
+                .collect::<Vec<_>>(),
+            vec![SqlDialectKind::MsSql]
+        );
+    }
+
+    #[test]
+    fn sqlite_has_no_exact_wide_integers() {
+        assert!(!SqlDialectKind::Sqlite.supports_wide_integers());
+        assert!(SqlDialectKind::MsSql.supports_wide_integers());
+        assert!(SqlDialectKind::PostgreSql.supports_wide_integers());
+    }
 }
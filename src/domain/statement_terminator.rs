@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+/// What follows each batch statement in SQL output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StatementTerminator {
+    /// `statement;`, without doubling a semicolon the input already ends with.
+    #[default]
+    Semicolon,
+    /// `statement` and `GO` on its own line, the batch separator sqlcmd expects.
+    Go,
+    /// The statement without its trailing semicolon, followed by this text.
+    Custom(String),
+}
+
+impl StatementTerminator {
+    pub fn terminate(&self, statement: &str) -> String {
+        let statement = statement.trim_end();
+        match self {
+            StatementTerminator::Semicolon if statement.ends_with(';') => statement.to_string(),
+            StatementTerminator::Semicolon => format!("{statement};"),
+            StatementTerminator::Go => format!("{}\nGO", strip_trailing_semicolons(statement)),
+            StatementTerminator::Custom(terminator) => {
+                format!("{}{terminator}", strip_trailing_semicolons(statement))
+            }
+        }
+    }
+}
+
+fn strip_trailing_semicolons(statement: &str) -> &str {
+    statement.trim_end_matches(';').trim_end()
+}
+
+impl std::fmt::Display for StatementTerminator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatementTerminator::Semicolon => f.write_str("semicolon"),
+            StatementTerminator::Go => f.write_str("go"),
+            StatementTerminator::Custom(terminator) => {
+                f.write_str(&terminator.escape_default().to_string())
+            }
+        }
+    }
+}
+
+impl FromStr for StatementTerminator {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        // In a custom terminator `\n` and `\t` stand for a newline and a tab, so `;\n`
+        // separates statements by a blank line.
+        match value.trim().to_ascii_lowercase().as_str() {
+            "semicolon" => Ok(StatementTerminator::Semicolon),
+            "go" => Ok(StatementTerminator::Go),
+            _ if value.trim().is_empty() => Err(anyhow!(
+                "Statement terminator must not be empty. Available values: semicolon,go,<custom text>"
+            )),
+            _ => Ok(StatementTerminator::Custom(
+                value.replace("\\n", "\n").replace("\\t", "\t"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatementTerminator;
+
+    #[test]
+    fn semicolon_terminator_does_not_double_existing_semicolon() {
+        let terminator = StatementTerminator::Semicolon;
+
+        assert_eq!(
+            terminator.terminate("DELETE FROM users"),
+            "DELETE FROM users;"
+        );
+        assert_eq!(
+            terminator.terminate("DELETE FROM users; "),
+            "DELETE FROM users;"
+        );
+    }
+
+    #[test]
+    fn go_terminator_replaces_semicolon_with_go_line() {
+        assert_eq!(
+            StatementTerminator::Go.terminate("DELETE FROM users;"),
+            "DELETE FROM users\nGO"
+        );
+    }
+
+    #[test]
+    fn parses_custom_terminator_with_escapes() {
+        let terminator = ";\\n"
+            .parse::<StatementTerminator>()
+            .expect("terminator should parse");
+
+        assert_eq!(terminator, StatementTerminator::Custom(";\n".to_string()));
+        assert_eq!(
+            terminator.terminate("DELETE FROM users;"),
+            "DELETE FROM users;\n"
+        );
+        assert_eq!(terminator.to_string(), ";\\n");
+        assert_eq!(
+            "GO".parse::<StatementTerminator>()
+                .expect("terminator should parse"),
+            StatementTerminator::Go
+        );
+        assert!("".parse::<StatementTerminator>().is_err());
+    }
+
+    #[test]
+    fn rejects_whitespace_only_terminator() {
+        assert!("   ".parse::<StatementTerminator>().is_err());
+        assert!("\t".parse::<StatementTerminator>().is_err());
+    }
+}
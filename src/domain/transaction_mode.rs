@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionMode {
+    None,
+    PerBatch,
+    EveryN(usize),
+}
+
+impl TransactionMode {
+    /// Number of batch statements wrapped by one `BEGIN`/`COMMIT` pair.
+    pub fn group_size(self) -> Option<usize> {
+        match self {
+            TransactionMode::None => None,
+            TransactionMode::PerBatch => Some(1),
+            TransactionMode::EveryN(batch_count) => Some(batch_count),
+        }
+    }
+}
+
+impl std::fmt::Display for TransactionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionMode::None => f.write_str("none"),
+            TransactionMode::PerBatch => f.write_str("per-batch"),
+            TransactionMode::EveryN(batch_count) => write!(f, "every-{batch_count}"),
+        }
+    }
+}
+
+impl FromStr for TransactionMode {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let normalized_value = value.trim().to_ascii_lowercase();
+        match normalized_value.as_str() {
+            "none" => Ok(TransactionMode::None),
+            "per-batch" | "per_batch" => Ok(TransactionMode::PerBatch),
+            _ => {
+                let batch_count = normalized_value
+                    .strip_prefix("every-")
+                    .or_else(|| normalized_value.strip_prefix("every_"))
+                    .and_then(|count| count.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Unsupported transaction mode: {value}. Available values: none,per-batch,every-<N>"
+                        )
+                    })?;
+                if batch_count == 0 {
+                    return Err(anyhow!("Transaction group size must be greater than 0"));
+                }
+                Ok(TransactionMode::EveryN(batch_count))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionMode;
+
+    #[test]
+    fn parses_every_n_transaction_mode() {
+        assert_eq!(
+            "every-25"
+                .parse::<TransactionMode>()
+                .expect("mode should parse"),
+            TransactionMode::EveryN(25)
+        );
+        assert_eq!(
+            "per-batch"
+                .parse::<TransactionMode>()
+                .expect("mode should parse"),
+            TransactionMode::PerBatch
+        );
+    }
+
+    #[test]
+    fn rejects_zero_sized_transaction_group() {
+        assert!("every-0".parse::<TransactionMode>().is_err());
+        assert!("sometimes".parse::<TransactionMode>().is_err());
+    }
+}
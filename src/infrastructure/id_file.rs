@@ -0,0 +1,140 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+/// Why an id file could not be turned into a list of ids.
+#[derive(Debug, Error)]
+pub enum IdFileError {
+    #[error("Unable to read ID file {}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Line {line_number} is not a numeric id: {value}")]
+    NonNumericId { line_number: usize, value: String },
+    #[error("ID file does not contain any ids")]
+    NoIds,
+    /// A parse failure of the content, tagged with the file it came from.
+    #[error("Invalid ID file: {}", path.display())]
+    InvalidFile {
+        path: PathBuf,
+        #[source]
+        source: Box<IdFileError>,
+    },
+}
+
+pub fn read_id_file(path: &Path) -> Result<Vec<i128>, IdFileError> {
+    parse_id_list(&read_content(path)?).map_err(|error| invalid_file(path, error))
+}
+
+/// Reads one opaque id per line, such as a UUID, to be quoted as a string literal.
+pub fn read_string_id_file(path: &Path) -> Result<Vec<String>, IdFileError> {
+    parse_string_id_list(&read_content(path)?).map_err(|error| invalid_file(path, error))
+}
+
+fn read_content(path: &Path) -> Result<String, IdFileError> {
+    fs::read_to_string(path).map_err(|source| IdFileError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn invalid_file(path: &Path, error: IdFileError) -> IdFileError {
+    IdFileError::InvalidFile {
+        path: path.to_path_buf(),
+        source: Box::new(error),
+    }
+}
+
+/// Parses one id per line; blank lines are skipped and duplicates are left for the slicer to drop.
+pub fn parse_id_list(content: &str) -> Result<Vec<i128>, IdFileError> {
+    let mut ids = Vec::new();
+    for (line_index, line) in content.lines().enumerate() {
+        let trimmed_line = line.trim();
+        if trimmed_line.is_empty() {
+            continue;
+        }
+
+        let id = trimmed_line
+            .parse::<i128>()
+            .map_err(|_| IdFileError::NonNumericId {
+                line_number: line_index + 1,
+                value: trimmed_line.to_string(),
+            })?;
+        ids.push(id);
+    }
+
+    if ids.is_empty() {
+        return Err(IdFileError::NoIds);
+    }
+    Ok(ids)
+}
+
+/// Like `parse_id_list`, but keeps each trimmed line as text.
+pub fn parse_string_id_list(content: &str) -> Result<Vec<String>, IdFileError> {
+    let ids = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    if ids.is_empty() {
+        return Err(IdFileError::NoIds);
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{IdFileError, parse_id_list, parse_string_id_list, read_id_file};
+
+    #[test]
+    fn skips_blank_lines_and_keeps_duplicates_for_slicer() {
+        let ids = parse_id_list("5\n\n  2 \n5\n").expect("ids should be parsed");
+
+        assert_eq!(ids, vec![5, 2, 5]);
+    }
+
+    #[test]
+    fn reports_line_number_of_non_numeric_id() {
+        let error = parse_id_list("1\n2\n\nabc\n").expect_err("non-numeric id should be rejected");
+
+        assert_eq!(error.to_string(), "Line 4 is not a numeric id: abc");
+    }
+
+    #[test]
+    fn rejects_file_without_ids() {
+        assert!(parse_id_list("\n  \n").is_err());
+        assert!(parse_string_id_list("\n  \n").is_err());
+    }
+
+    #[test]
+    fn keeps_string_ids_as_trimmed_text() {
+        let ids = parse_string_id_list(" 0b5c-41 \n\nO'Brien\n").expect("ids should be parsed");
+
+        assert_eq!(ids, vec!["0b5c-41".to_string(), "O'Brien".to_string()]);
+    }
+
+    #[test]
+    fn names_the_file_of_invalid_content() {
+        let error = read_id_file(Path::new("Cargo.toml")).expect_err("toml is not an id file");
+
+        assert!(matches!(
+            &error,
+            IdFileError::InvalidFile { path, source }
+                if path == Path::new("Cargo.toml")
+                    && matches!(**source, IdFileError::NonNumericId { line_number: 1, .. })
+        ));
+        assert!(matches!(
+            read_id_file(Path::new("missing-ids.txt")),
+            Err(IdFileError::Read { .. })
+        ));
+    }
+}
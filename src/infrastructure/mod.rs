@@ -1 +1,2 @@
+pub mod id_file;
 pub mod sql_batch_template;
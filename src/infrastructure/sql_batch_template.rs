@@ -1,6 +1,10 @@
-use anyhow::{Context, Result, anyhow};
 use sqlparser::{
-    ast::{BinaryOperator, Expr, Ident, SetExpr, Statement, TableFactor, Value, ValueWithSpan},
+    ast::{
+        BinaryOperator, CastKind, DataType, ExactNumberInfo, Expr, Function, FunctionArg,
+        FunctionArgExpr, FunctionArgumentList, FunctionArguments, Ident, MergeClauseKind,
+        ObjectName, OrderByExpr, OrderByOptions, Query, SetExpr, Statement, TableFactor, Value,
+        ValueWithSpan, With,
+    },
     dialect::{
         DuckDbDialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect,
         SQLiteDialect, SnowflakeDialect,
@@ -8,52 +12,761 @@ use sqlparser::{
     parser::Parser,
 };
 
-use crate::domain::sql_dialect::SqlDialectKind;
+use chrono::NaiveDate;
+
+use crate::domain::{
+    date_batch::DateBatchRange,
+    error::DomainError,
+    id_batch::{BatchingMode, IdBatchRange, IdBatchSlicer, IdShard, RangeBounds},
+    sql_dialect::SqlDialectKind,
+};
+
+/// `10^38`, the smallest magnitude `DECIMAL(38,0)` cannot hold.
+const MAX_DECIMAL_MAGNITUDE: u128 = 10u128.pow(38);
 
 #[derive(Debug, Clone)]
 pub struct SqlParserBatchTemplate {
     base_statement: Statement,
-    qualified_primary_key_expr: Expr,
+    /// A single column, or an `Expr::Tuple` whose last column is ranged and the rest are fixed.
+    qualified_id_column_expr: Expr,
+    key_prefix_values: Vec<Expr>,
+    dialect_kind: SqlDialectKind,
+    range_bounds: RangeBounds,
 }
 
 impl SqlParserBatchTemplate {
-    pub fn parse(raw_sql: &str, dialect_kind: SqlDialectKind, primary_key: &str) -> Result<Self> {
+    /// `range_column` is usually the primary key and may list several comma-separated columns;
+    /// `key_prefix` then holds the fixed values of every column but the last, which is the one
+    /// being ranged.
+    pub fn parse(
+        raw_sql: &str,
+        dialect_kind: SqlDialectKind,
+        range_column: &str,
+        key_prefix: &[String],
+        range_bounds: RangeBounds,
+        batching_mode: BatchingMode,
+        quote_identifiers: bool,
+    ) -> Result<Self, DomainError> {
         if raw_sql.trim().is_empty() {
-            return Err(anyhow!("Input SQL must not be empty"));
+            return Err(DomainError::EmptySql);
         }
 
         let statement = parse_single_statement(raw_sql, dialect_kind)?;
+        if matches!(statement, Statement::Merge(_)) && !dialect_kind.supports_merge() {
+            return Err(DomainError::UnsupportedStatement(format!(
+                "Dialect '{dialect_kind}' does not support MERGE; choose snowflake, mssql, postgres or duckdb"
+            )));
+        }
+        if batching_mode == BatchingMode::LimitBased {
+            ensure_limit_batching_is_supported(&statement, dialect_kind)?;
+        }
 
         let table_alias = extract_main_table_alias(&statement);
-        let qualified_primary_key_expr = build_primary_key_expr(primary_key, table_alias)?;
+        let quote_style = quote_identifiers.then(|| dialect_kind.identifier_quote_style());
+        let qualified_id_column_expr =
+            build_id_column_expr(range_column, table_alias, quote_style)?;
+        let key_column_count = match &qualified_id_column_expr {
+            Expr::Tuple(key_columns) => key_columns.len(),
+            _ => 1,
+        };
+        if key_prefix.len() + 1 != key_column_count {
+            return Err(DomainError::InvalidIdColumn(format!(
+                "Id column {} has {} leading column(s) but {} key prefix value(s) were given",
+                range_column.trim(),
+                key_column_count - 1,
+                key_prefix.len()
+            )));
+        }
+        if key_column_count > 1 && batching_mode == BatchingMode::LimitBased {
+            return Err(DomainError::UnsupportedStatement(
+                "LIMIT-based batching only supports a single primary key column".to_string(),
+            ));
+        }
 
         Ok(Self {
             base_statement: statement,
-            qualified_primary_key_expr,
+            qualified_id_column_expr,
+            key_prefix_values: key_prefix
+                .iter()
+                .map(|value| build_key_prefix_value_expr(value, dialect_kind))
+                .collect::<Result<_, _>>()?,
+            dialect_kind,
+            range_bounds,
+        })
+    }
+
+    pub fn render_for_range(&self, start_id: i128, end_id: i128) -> Result<String, DomainError> {
+        self.render_with_condition(self.build_id_range_condition(start_id, end_id)?)
+    }
+
+    /// Pairs every range of `slicer`, in its order, with the statement rendered for it.
+    pub fn render_all(
+        &self,
+        slicer: &IdBatchSlicer,
+    ) -> impl Iterator<Item = Result<(IdBatchRange, String), DomainError>> + '_ {
+        slicer.iter_ranges().map(|id_range| {
+            self.render_for_range(id_range.start_id, id_range.end_id)
+                .map(|sql| (id_range, sql))
+        })
+    }
+
+    pub fn render_for_ids(&self, ids: &[i128]) -> Result<String, DomainError> {
+        self.render_with_condition(self.build_id_list_condition(ids)?)
+    }
+
+    /// `pk IN ('a', 'b')`; quotes inside an id are doubled, so the literal cannot end early.
+    pub fn render_for_string_ids(&self, ids: &[String]) -> Result<String, DomainError> {
+        self.render_with_condition(self.build_string_id_list_condition(ids)?)
+    }
+
+    /// `SELECT COUNT(*)` over the rows the range batch will touch; `None` unless the statement is
+    /// an UPDATE or DELETE.
+    pub fn render_verification_for_range(
+        &self,
+        start_id: i128,
+        end_id: i128,
+    ) -> Result<Option<String>, DomainError> {
+        self.render_verification(self.build_id_range_condition(start_id, end_id)?)
+    }
+
+    pub fn render_verification_for_ids(&self, ids: &[i128]) -> Result<Option<String>, DomainError> {
+        self.render_verification(self.build_id_list_condition(ids)?)
+    }
+
+    pub fn render_verification_for_string_ids(
+        &self,
+        ids: &[String],
+    ) -> Result<Option<String>, DomainError> {
+        self.render_verification(self.build_string_id_list_condition(ids)?)
+    }
+
+    pub fn render_for_shard(&self, shard: IdShard) -> Result<String, DomainError> {
+        self.render_with_condition(self.build_shard_condition(shard))
+    }
+
+    pub fn render_verification_for_shard(
+        &self,
+        shard: IdShard,
+    ) -> Result<Option<String>, DomainError> {
+        self.render_verification(self.build_shard_condition(shard))
+    }
+
+    /// Dates are always matched half-open, `col >= 'start' AND col < 'end'`, whatever the
+    /// configured id range bounds.
+    pub fn render_for_date_range(&self, date_range: DateBatchRange) -> Result<String, DomainError> {
+        self.render_with_condition(self.build_date_range_condition(date_range))
+    }
+
+    pub fn render_verification_for_date_range(
+        &self,
+        date_range: DateBatchRange,
+    ) -> Result<Option<String>, DomainError> {
+        self.render_verification(self.build_date_range_condition(date_range))
+    }
+
+    /// Whether the verification count had to drop `WHERE` conditions on joined tables, so it
+    /// may count more rows than the batch touches.
+    pub fn verification_is_approximate(&self) -> bool {
+        self.build_verification_selection().1
+    }
+
+    fn build_date_range_condition(&self, date_range: DateBatchRange) -> Expr {
+        let build_half_open_range = |column_expr: &Expr| {
+            build_comparison_range(
+                column_expr,
+                build_date_expr(date_range.start_date),
+                BinaryOperator::Lt,
+                build_date_expr(date_range.end_date),
+            )
+        };
+        match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => self.with_key_prefix_equalities(
+                key_columns,
+                build_half_open_range(last_key_column(key_columns)),
+            ),
+            primary_key_expr => build_half_open_range(primary_key_expr),
+        }
+    }
+
+    fn build_id_range_condition(&self, start_id: i128, end_id: i128) -> Result<Expr, DomainError> {
+        let (start_expr, end_expr) = (
+            build_id_literal_expr(start_id, self.dialect_kind)?,
+            build_id_literal_expr(end_id, self.dialect_kind)?,
+        );
+        Ok(match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) if self.dialect_kind.supports_row_value_comparison() => {
+                let with_prefix = |bound: Expr| {
+                    let mut values = self.key_prefix_values.clone();
+                    values.push(bound);
+                    Expr::Tuple(values)
+                };
+                let upper_operator = match self.range_bounds {
+                    RangeBounds::InclusiveBetween => BinaryOperator::LtEq,
+                    RangeBounds::HalfOpen => BinaryOperator::Lt,
+                };
+                build_comparison_range(
+                    &Expr::Tuple(key_columns.clone()),
+                    with_prefix(start_expr),
+                    upper_operator,
+                    with_prefix(end_expr),
+                )
+            }
+            Expr::Tuple(key_columns) => self.with_key_prefix_equalities(
+                key_columns,
+                self.build_range_condition(last_key_column(key_columns), start_expr, end_expr),
+            ),
+            primary_key_expr => self.build_range_condition(primary_key_expr, start_expr, end_expr),
         })
     }
 
-    pub fn render_for_range(&self, start_id: i128, end_id: i128) -> Result<String> {
-        let batch_condition_expr = Expr::Between {
-            expr: Box::new(self.qualified_primary_key_expr.clone()),
+    fn build_id_list_condition(&self, ids: &[i128]) -> Result<Expr, DomainError> {
+        self.build_in_list_condition(
+            ids.iter()
+                .map(|id| build_id_literal_expr(*id, self.dialect_kind))
+                .collect::<Result<_, _>>()?,
+        )
+    }
+
+    fn build_string_id_list_condition(&self, ids: &[String]) -> Result<Expr, DomainError> {
+        self.build_in_list_condition(
+            ids.iter()
+                .map(|id| Expr::Value(ValueWithSpan::from(Value::SingleQuotedString(id.clone()))))
+                .collect(),
+        )
+    }
+
+    fn build_in_list_condition(&self, id_exprs: Vec<Expr>) -> Result<Expr, DomainError> {
+        if id_exprs.is_empty() {
+            return Err(DomainError::EmptyIdBatch);
+        }
+
+        let build_in_list = |expr: &Expr| Expr::InList {
+            expr: Box::new(expr.clone()),
+            list: id_exprs.clone(),
             negated: false,
-            low: Box::new(Expr::Value(ValueWithSpan::from(Value::Number(
-                start_id.to_string(),
-                false,
-            )))),
-            high: Box::new(Expr::Value(ValueWithSpan::from(Value::Number(
-                end_id.to_string(),
-                false,
-            )))),
         };
+        Ok(match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => self.with_key_prefix_equalities(
+                key_columns,
+                build_in_list(last_key_column(key_columns)),
+            ),
+            primary_key_expr => build_in_list(primary_key_expr),
+        })
+    }
+
+    /// `pk % count = index`, or `MOD(pk, count) = index` where the dialect lacks the operator.
+    fn build_shard_condition(&self, shard: IdShard) -> Expr {
+        let build_residue_condition = |column_expr: &Expr| {
+            let count_expr = build_number_expr(shard.count as i128);
+            let modulo_expr = if self.dialect_kind.supports_modulo_operator() {
+                Expr::BinaryOp {
+                    left: Box::new(column_expr.clone()),
+                    op: BinaryOperator::Modulo,
+                    right: Box::new(count_expr),
+                }
+            } else {
+                build_function_call_expr("MOD", vec![column_expr.clone(), count_expr])
+            };
+            Expr::BinaryOp {
+                left: Box::new(modulo_expr),
+                op: BinaryOperator::Eq,
+                right: Box::new(build_number_expr(shard.index as i128)),
+            }
+        };
+        match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => self.with_key_prefix_equalities(
+                key_columns,
+                build_residue_condition(last_key_column(key_columns)),
+            ),
+            primary_key_expr => build_residue_condition(primary_key_expr),
+        }
+    }
+
+    fn build_range_condition(&self, column_expr: &Expr, start_expr: Expr, end_expr: Expr) -> Expr {
+        match self.range_bounds {
+            RangeBounds::InclusiveBetween => Expr::Between {
+                expr: Box::new(column_expr.clone()),
+                negated: false,
+                low: Box::new(start_expr),
+                high: Box::new(end_expr),
+            },
+            RangeBounds::HalfOpen => {
+                build_comparison_range(column_expr, start_expr, BinaryOperator::Lt, end_expr)
+            }
+        }
+    }
+
+    /// Spells a composite key as `lead = v AND <condition on the last column>`.
+    fn with_key_prefix_equalities(
+        &self,
+        key_columns: &[Expr],
+        last_column_condition: Expr,
+    ) -> Expr {
+        match self.key_prefix_condition(key_columns) {
+            Some(key_prefix_condition) => Expr::BinaryOp {
+                left: Box::new(key_prefix_condition),
+                op: BinaryOperator::And,
+                right: Box::new(last_column_condition),
+            },
+            None => last_column_condition,
+        }
+    }
+
+    /// `lead1 = v1 AND lead2 = v2 ...` for the fixed columns of a composite key.
+    fn key_prefix_condition(&self, key_columns: &[Expr]) -> Option<Expr> {
+        key_columns
+            .iter()
+            .zip(&self.key_prefix_values)
+            .map(|(column_expr, value_expr)| Expr::BinaryOp {
+                left: Box::new(column_expr.clone()),
+                op: BinaryOperator::Eq,
+                right: Box::new(value_expr.clone()),
+            })
+            .reduce(|condition, equality| Expr::BinaryOp {
+                left: Box::new(condition),
+                op: BinaryOperator::And,
+                right: Box::new(equality),
+            })
+    }
+
+    /// The first comparison or `BETWEEN` in the input `WHERE` that already bounds the ranged key
+    /// column, which usually means a manual range was left in the SQL.
+    pub fn find_id_column_predicate(&self) -> Option<String> {
+        let ranged_key_expr = match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => last_key_column(key_columns),
+            primary_key_expr => primary_key_expr,
+        };
+        let (key_column, mut key_qualifiers) = match ranged_key_expr {
+            Expr::Identifier(key_column) => (key_column, Vec::new()),
+            Expr::CompoundIdentifier(key_parts) => {
+                let (key_column, qualifier_parts) = key_parts.split_last()?;
+                (key_column, qualifier_parts.last().into_iter().collect())
+            }
+            _ => return None,
+        };
+        key_qualifiers.extend(self.main_table_qualifiers());
+
+        let same_ident =
+            |left: &Ident, right: &Ident| left.value.eq_ignore_ascii_case(&right.value);
+        let is_key_column = |expr: &Expr| match expr {
+            Expr::Identifier(ident) => same_ident(ident, key_column),
+            Expr::CompoundIdentifier(parts) => match parts.as_slice() {
+                [.., qualifier, column] => {
+                    same_ident(column, key_column)
+                        && key_qualifiers
+                            .iter()
+                            .any(|key_qualifier| same_ident(qualifier, key_qualifier))
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        let original_selection = extract_selection(&self.base_statement)?;
+        find_key_predicate(original_selection, &is_key_column).map(ToString::to_string)
+    }
+
+    /// Builds `SELECT MIN(pk), MAX(pk) FROM <target table>`, whose result is the id range to slice.
+    pub fn render_range_bootstrap_query(&self) -> Result<String, DomainError> {
+        let table_factor = extract_main_table_factor(&self.base_statement).ok_or_else(|| {
+            DomainError::UnsupportedStatement(
+                "Unable to find the target table for a MIN/MAX bootstrap query".to_string(),
+            )
+        })?;
+        let (ranged_key_expr, key_prefix_condition) = match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => (
+                last_key_column(key_columns),
+                self.key_prefix_condition(key_columns),
+            ),
+            primary_key_expr => (primary_key_expr, None),
+        };
+
+        let bootstrap_sql =
+            format!("SELECT MIN({ranged_key_expr}), MAX({ranged_key_expr}) FROM {table_factor}");
+        let mut bootstrap_statement = parse_single_statement(&bootstrap_sql, self.dialect_kind)?;
+        if let Some(key_prefix_condition) = key_prefix_condition {
+            inject_batch_condition(&mut bootstrap_statement, key_prefix_condition)?;
+        }
+        Ok(bootstrap_statement.to_string())
+    }
+
+    /// Re-parses a rendered batch with the template's dialect, as a check on the rewriting.
+    pub fn validate_rendered_sql(&self, rendered_sql: &str) -> Result<(), DomainError> {
+        // sqlparser cannot parse the `ORDER BY pk LIMIT n` that `render_with_limit` appends to an
+        // UPDATE as text, so only the statement in front of it is parsed again.
+        let limit_clause = format!(" ORDER BY {} LIMIT ", self.qualified_id_column_expr);
+        let parsed_sql = match rendered_sql.rsplit_once(&limit_clause) {
+            Some((update_sql, batch_size))
+                if matches!(self.base_statement, Statement::Update(_))
+                    && !batch_size.is_empty()
+                    && batch_size.bytes().all(|byte| byte.is_ascii_digit()) =>
+            {
+                update_sql
+            }
+            _ => rendered_sql,
+        };
+        parse_single_statement(parsed_sql, self.dialect_kind).map(|_| ())
+    }
+
+    /// Renders the statement with its `WHERE` untouched plus `ORDER BY pk LIMIT batch_size`.
+    pub fn render_with_limit(&self, batch_size: usize) -> Result<String, DomainError> {
+        let mut statement_for_batch = self.base_statement.clone();
+        let primary_key_expr = &self.qualified_id_column_expr;
+        match &mut statement_for_batch {
+            Statement::Delete(delete_statement) => {
+                delete_statement.order_by = vec![OrderByExpr {
+                    expr: primary_key_expr.clone(),
+                    options: OrderByOptions {
+                        asc: None,
+                        nulls_first: None,
+                    },
+                    with_fill: None,
+                }];
+                delete_statement.limit = Some(build_number_expr(batch_size as i128));
+                Ok(statement_for_batch.to_string())
+            }
+            Statement::Update(update_statement) => {
+                // sqlparser has no ORDER BY slot on UPDATE, so the clause is appended as text.
+                update_statement.limit = None;
+                Ok(format!(
+                    "{statement_for_batch} ORDER BY {primary_key_expr} LIMIT {batch_size}"
+                ))
+            }
+            _ => Err(DomainError::UnsupportedStatement(
+                "LIMIT-based batching only supports UPDATE and DELETE statements".to_string(),
+            )),
+        }
+    }
 
+    fn render_with_condition(&self, batch_condition_expr: Expr) -> Result<String, DomainError> {
         let mut statement_for_batch = self.base_statement.clone();
         inject_batch_condition(&mut statement_for_batch, batch_condition_expr)?;
         Ok(statement_for_batch.to_string())
     }
+
+    /// The ranged key, as it is spelled in each batch condition, when the input is an UPDATE or
+    /// DELETE without a `WHERE`; that condition is then the only thing narrowing the rows.
+    pub fn find_unfiltered_dml_key(&self) -> Option<String> {
+        let (Statement::Update(_) | Statement::Delete(_)) =
+            split_cte_wrapped_dml(&self.base_statement).0
+        else {
+            return None;
+        };
+        if extract_selection(&self.base_statement).is_some() {
+            return None;
+        }
+        Some(match &self.qualified_id_column_expr {
+            Expr::Tuple(key_columns) => last_key_column(key_columns).to_string(),
+            primary_key_expr => primary_key_expr.to_string(),
+        })
+    }
+
+    /// Rewrites an UPDATE/DELETE into `SELECT COUNT(*) FROM <target table> WHERE <batch condition>`.
+    fn render_verification(
+        &self,
+        batch_condition_expr: Expr,
+    ) -> Result<Option<String>, DomainError> {
+        let (dml_statement, with_clause) = split_cte_wrapped_dml(&self.base_statement);
+        if !matches!(dml_statement, Statement::Update(_) | Statement::Delete(_)) {
+            return Ok(None);
+        }
+        let Some(table_factor) = extract_main_table_factor(dml_statement) else {
+            return Ok(None);
+        };
+
+        let mut verification_condition = self.build_verification_selection().0;
+        merge_selection(&mut verification_condition, batch_condition_expr);
+
+        // The original WHERE may read from the CTEs, so the count query keeps them.
+        let cte_prefix = with_clause.map(|with_clause| format!("{with_clause} "));
+        let mut verification_statement = parse_single_statement(
+            &format!(
+                "{}SELECT COUNT(*) FROM {table_factor}",
+                cte_prefix.unwrap_or_default()
+            ),
+            self.dialect_kind,
+        )?;
+        if let Some(verification_condition) = verification_condition {
+            inject_batch_condition(&mut verification_statement, verification_condition)?;
+        }
+        Ok(Some(verification_statement.to_string()))
+    }
+
+    /// The original `WHERE` as the target-table count can evaluate it. Only the target table is
+    /// selected, so with joins just the `AND` conditions qualified by the target table are kept;
+    /// the flag tells whether any condition was dropped.
+    fn build_verification_selection(&self) -> (Option<Expr>, bool) {
+        let Some(original_selection) = extract_selection(&self.base_statement) else {
+            return (None, false);
+        };
+        if !has_joined_tables(&self.base_statement) {
+            return (Some(original_selection.clone()), false);
+        }
+
+        let target_qualifiers = self.main_table_qualifiers();
+        let mut conditions = Vec::new();
+        collect_and_conditions(original_selection, &mut conditions);
+        let condition_count = conditions.len();
+        let kept_conditions = conditions
+            .into_iter()
+            .filter(|condition| references_only_qualifiers(condition, &target_qualifiers))
+            .cloned()
+            .collect::<Vec<_>>();
+        let has_dropped_conditions = kept_conditions.len() < condition_count;
+        let kept_selection = kept_conditions
+            .into_iter()
+            .reduce(|left, right| Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            });
+        (kept_selection, has_dropped_conditions)
+    }
+
+    /// The names a column of the target table can be qualified with: its table name and alias.
+    fn main_table_qualifiers(&self) -> Vec<&Ident> {
+        match extract_main_table_factor(&self.base_statement) {
+            Some(TableFactor::Table { name, alias, .. }) => name
+                .0
+                .last()
+                .and_then(|part| part.as_ident())
+                .into_iter()
+                .chain(alias.as_ref().map(|table_alias| &table_alias.name))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// sqlparser parses `WITH ... UPDATE|DELETE` as a query; this returns the UPDATE/DELETE inside
+/// with its `WITH` clause, and any other statement as is.
+fn split_cte_wrapped_dml(statement: &Statement) -> (&Statement, Option<&With>) {
+    if let Statement::Query(query) = statement
+        && let SetExpr::Update(dml_statement) | SetExpr::Delete(dml_statement) = query.body.as_ref()
+    {
+        return (dml_statement, query.with.as_ref());
+    }
+    (statement, None)
+}
+
+/// Whether an UPDATE/DELETE touches or reads more than its single target table.
+fn has_joined_tables(statement: &Statement) -> bool {
+    match split_cte_wrapped_dml(statement).0 {
+        Statement::Update(update_statement) => {
+            !update_statement.table.joins.is_empty() || update_statement.from.is_some()
+        }
+        Statement::Delete(delete_statement) => {
+            let from_tables = match &delete_statement.from {
+                sqlparser::ast::FromTable::WithFromKeyword(tables) => tables,
+                sqlparser::ast::FromTable::WithoutKeyword(tables) => tables,
+            };
+            !delete_statement.tables.is_empty()
+                || delete_statement.using.is_some()
+                || from_tables.len() != 1
+                || from_tables.iter().any(|table| !table.joins.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Splits `a AND (b AND c)` into `[a, b, c]`.
+fn collect_and_conditions<'a>(expr: &'a Expr, conditions: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            collect_and_conditions(left, conditions);
+            collect_and_conditions(right, conditions);
+        }
+        Expr::Nested(nested_expr)
+            if matches!(
+                **nested_expr,
+                Expr::BinaryOp {
+                    op: BinaryOperator::And,
+                    ..
+                }
+            ) =>
+        {
+            collect_and_conditions(nested_expr, conditions);
+        }
+        _ => conditions.push(expr),
+    }
+}
+
+/// Whether every column in `expr` is qualified by one of `qualifiers`. Unqualified columns may
+/// belong to a joined table, and unlisted expression kinds such as subqueries are not inspected,
+/// so both count as referencing other tables.
+fn references_only_qualifiers(expr: &Expr, qualifiers: &[&Ident]) -> bool {
+    let check = |expr: &Expr| references_only_qualifiers(expr, qualifiers);
+    match expr {
+        Expr::Value(_) => true,
+        Expr::CompoundIdentifier(parts) => match parts.as_slice() {
+            [.., qualifier, _] => qualifiers.iter().any(|target_qualifier| {
+                target_qualifier
+                    .value
+                    .eq_ignore_ascii_case(&qualifier.value)
+            }),
+            _ => false,
+        },
+        Expr::BinaryOp { left, right, .. } => check(left) && check(right),
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::IsTrue(expr)
+        | Expr::IsFalse(expr)
+        | Expr::IsNotTrue(expr)
+        | Expr::IsNotFalse(expr) => check(expr),
+        Expr::Between {
+            expr, low, high, ..
+        } => check(expr) && check(low) && check(high),
+        Expr::InList { expr, list, .. } => check(expr) && list.iter().all(check),
+        Expr::Like { expr, pattern, .. } | Expr::ILike { expr, pattern, .. } => {
+            check(expr) && check(pattern)
+        }
+        _ => false,
+    }
+}
+
+/// Walks `AND`/`OR` trees and parentheses for a comparison or `BETWEEN` on the key column.
+fn find_key_predicate<'a>(
+    expr: &'a Expr,
+    is_key_column: &dyn Fn(&Expr) -> bool,
+) -> Option<&'a Expr> {
+    match expr {
+        Expr::BinaryOp { left, op, right } => {
+            let is_comparison = matches!(
+                op,
+                BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq
+            );
+            if is_comparison && (is_key_column(left) || is_key_column(right)) {
+                return Some(expr);
+            }
+            find_key_predicate(left, is_key_column)
+                .or_else(|| find_key_predicate(right, is_key_column))
+        }
+        Expr::Nested(nested_expr) => find_key_predicate(nested_expr, is_key_column),
+        Expr::Between {
+            expr: between_expr, ..
+        } if is_key_column(between_expr) => Some(expr),
+        _ => None,
+    }
+}
+
+fn build_number_expr(value: i128) -> Expr {
+    Expr::Value(ValueWithSpan::from(Value::Number(value.to_string(), false)))
+}
+
+/// Literal for an id bound. Values that fit in `i64` stay bare everywhere; wider values are
+/// wrapped in `CAST('...' AS DECIMAL(38,0))` on dialects that would reject them bare, and
+/// rejected where no exact literal exists.
+fn build_id_literal_expr(value: i128, dialect_kind: SqlDialectKind) -> Result<Expr, DomainError> {
+    let unrepresentable = |reason| DomainError::UnrepresentableId {
+        id: value,
+        dialect_kind,
+        reason,
+    };
+    if i64::try_from(value).is_ok() {
+        return Ok(build_number_expr(value));
+    }
+    if !dialect_kind.supports_wide_integers() {
+        return Err(unrepresentable(
+            "integers beyond 64 bits would be read as lossy REAL values",
+        ));
+    }
+    if !dialect_kind.requires_cast_for_wide_integers() {
+        return Ok(build_number_expr(value));
+    }
+    if value.unsigned_abs() >= MAX_DECIMAL_MAGNITUDE {
+        return Err(unrepresentable("DECIMAL(38,0) holds at most 38 digits"));
+    }
+    Ok(Expr::Cast {
+        kind: CastKind::Cast,
+        expr: Box::new(Expr::Value(ValueWithSpan::from(Value::SingleQuotedString(
+            value.to_string(),
+        )))),
+        data_type: DataType::Decimal(ExactNumberInfo::PrecisionAndScale(38, 0)),
+        array: false,
+        format: None,
+    })
+}
+
+fn build_function_call_expr(function_name: &str, args: Vec<Expr>) -> Expr {
+    Expr::Function(Function {
+        name: ObjectName::from(vec![Ident::new(function_name)]),
+        uses_odbc_syntax: false,
+        parameters: FunctionArguments::None,
+        args: FunctionArguments::List(FunctionArgumentList {
+            duplicate_treatment: None,
+            args: args
+                .into_iter()
+                .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+                .collect(),
+            clauses: Vec::new(),
+        }),
+        filter: None,
+        null_treatment: None,
+        over: None,
+        within_group: Vec::new(),
+    })
+}
+
+fn build_key_prefix_value_expr(
+    value: &str,
+    dialect_kind: SqlDialectKind,
+) -> Result<Expr, DomainError> {
+    let trimmed_value = value.trim();
+    match trimmed_value.parse::<i128>() {
+        Ok(number) => build_id_literal_expr(number, dialect_kind),
+        Err(_) => Ok(Expr::Value(ValueWithSpan::from(Value::SingleQuotedString(
+            trimmed_value.to_string(),
+        )))),
+    }
+}
+
+fn build_date_expr(date: NaiveDate) -> Expr {
+    Expr::Value(ValueWithSpan::from(Value::SingleQuotedString(
+        date.format("%Y-%m-%d").to_string(),
+    )))
+}
+
+fn build_comparison_range(
+    expr: &Expr,
+    low: Expr,
+    upper_operator: BinaryOperator,
+    high: Expr,
+) -> Expr {
+    Expr::BinaryOp {
+        left: Box::new(Expr::BinaryOp {
+            left: Box::new(expr.clone()),
+            op: BinaryOperator::GtEq,
+            right: Box::new(low),
+        }),
+        op: BinaryOperator::And,
+        right: Box::new(Expr::BinaryOp {
+            left: Box::new(expr.clone()),
+            op: upper_operator,
+            right: Box::new(high),
+        }),
+    }
+}
+
+fn last_key_column(key_columns: &[Expr]) -> &Expr {
+    key_columns
+        .last()
+        .expect("composite primary keys always have at least two columns")
 }
 
-fn parse_single_statement(raw_sql: &str, dialect_kind: SqlDialectKind) -> Result<Statement> {
+fn parse_single_statement(
+    raw_sql: &str,
+    dialect_kind: SqlDialectKind,
+) -> Result<Statement, DomainError> {
     let statements = match dialect_kind {
         SqlDialectKind::Generic => Parser::parse_sql(&GenericDialect {}, raw_sql),
         SqlDialectKind::MySql => Parser::parse_sql(&MySqlDialect {}, raw_sql),
@@ -63,26 +776,99 @@ fn parse_single_statement(raw_sql: &str, dialect_kind: SqlDialectKind) -> Result
         SqlDialectKind::Snowflake => Parser::parse_sql(&SnowflakeDialect {}, raw_sql),
         SqlDialectKind::DuckDb => Parser::parse_sql(&DuckDbDialect {}, raw_sql),
     }
-    .context("Unable to parse SQL with selected dialect")?;
+    .map_err(|error| DomainError::ParseFailed(error.to_string()))?;
 
     let statement_count = statements.len();
     if statement_count != 1 {
-        return Err(anyhow!(
-            "Input SQL must contain exactly one statement, but got {}",
-            statement_count
-        ));
+        return Err(DomainError::StatementCount(statement_count));
     }
 
     statements
         .into_iter()
         .next()
-        .ok_or_else(|| anyhow!("Input SQL must contain exactly one statement"))
+        .ok_or(DomainError::StatementCount(0))
+}
+
+fn ensure_limit_batching_is_supported(
+    statement: &Statement,
+    dialect_kind: SqlDialectKind,
+) -> Result<(), DomainError> {
+    let statement_kind = match statement {
+        Statement::Update(update_statement) => {
+            if has_joined_tables(statement) {
+                return Err(DomainError::UnsupportedStatement(
+                    "LIMIT-based batching does not support multi-table UPDATE statements"
+                        .to_string(),
+                ));
+            }
+            if update_statement.returning.is_some() {
+                return Err(DomainError::UnsupportedStatement(
+                    "LIMIT-based batching does not support UPDATE ... RETURNING".to_string(),
+                ));
+            }
+            "UPDATE"
+        }
+        Statement::Delete(_) => {
+            if has_joined_tables(statement) {
+                return Err(DomainError::UnsupportedStatement(
+                    "LIMIT-based batching does not support multi-table DELETE statements"
+                        .to_string(),
+                ));
+            }
+            "DELETE"
+        }
+        _ if split_cte_wrapped_dml(statement).1.is_some() => {
+            return Err(DomainError::UnsupportedStatement(
+                "LIMIT-based batching does not support UPDATE/DELETE with a WITH clause"
+                    .to_string(),
+            ));
+        }
+        _ => {
+            return Err(DomainError::UnsupportedStatement(
+                "LIMIT-based batching only supports UPDATE and DELETE statements".to_string(),
+            ));
+        }
+    };
+
+    if !dialect_kind.supports_order_by_limit_in_dml() {
+        return Err(DomainError::UnsupportedStatement(format!(
+            "Dialect '{dialect_kind}' does not support ORDER BY ... LIMIT on {statement_kind}; LIMIT-based batching requires mysql"
+        )));
+    }
+    Ok(())
+}
+
+/// `quote_style` applies to the parts of `primary_key` only; the alias is kept as written in the SQL.
+fn build_id_column_expr(
+    primary_key: &str,
+    table_alias: Option<&str>,
+    quote_style: Option<char>,
+) -> Result<Expr, DomainError> {
+    if primary_key.contains(',') {
+        let key_columns = primary_key
+            .split(',')
+            .map(|column| build_key_column_expr(column, table_alias, quote_style))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Expr::Tuple(key_columns));
+    }
+
+    build_key_column_expr(primary_key, table_alias, quote_style)
 }
 
-fn build_primary_key_expr(primary_key: &str, table_alias: Option<&str>) -> Result<Expr> {
+fn build_key_column_expr(
+    primary_key: &str,
+    table_alias: Option<&str>,
+    quote_style: Option<char>,
+) -> Result<Expr, DomainError> {
+    let build_ident = |value: &str| match quote_style {
+        Some(quote) => Ident::with_quote(quote, value),
+        None => Ident::new(value),
+    };
     let trimmed_primary_key = primary_key.trim();
     if trimmed_primary_key.is_empty() {
-        return Err(anyhow!("Primary key must not be empty"));
+        return Err(DomainError::InvalidIdColumn(
+            "Id column must not be empty".to_string(),
+        ));
     }
 
     if trimmed_primary_key.contains('.') {
@@ -90,12 +876,13 @@ fn build_primary_key_expr(primary_key: &str, table_alias: Option<&str>) -> Resul
             .split('.')
             .map(str::trim)
             .filter(|part| !part.is_empty())
-            .map(Ident::new)
+            .map(build_ident)
             .collect::<Vec<_>>();
 
         if identifier_parts.len() < 2 {
-            return Err(anyhow!(
-                "Qualified primary key must contain both table and column, for example: users.id"
+            return Err(DomainError::InvalidIdColumn(
+                "Qualified id column must contain both table and column, for example: users.id"
+                    .to_string(),
             ));
         }
 
@@ -105,30 +892,65 @@ fn build_primary_key_expr(primary_key: &str, table_alias: Option<&str>) -> Resul
     if let Some(alias_name) = table_alias {
         return Ok(Expr::CompoundIdentifier(vec![
             Ident::new(alias_name),
-            Ident::new(trimmed_primary_key),
+            build_ident(trimmed_primary_key),
         ]));
     }
 
-    Ok(Expr::Identifier(Ident::new(trimmed_primary_key)))
+    Ok(Expr::Identifier(build_ident(trimmed_primary_key)))
 }
 
 fn extract_main_table_alias(statement: &Statement) -> Option<&str> {
-    match statement {
-        Statement::Update(update_statement) => {
-            extract_alias_from_table_factor(&update_statement.table.relation)
-        }
+    extract_main_table_factor(statement).and_then(extract_alias_from_table_factor)
+}
+
+/// The table whose primary key is being ranged.
+fn extract_main_table_factor(statement: &Statement) -> Option<&TableFactor> {
+    match split_cte_wrapped_dml(statement).0 {
+        Statement::Update(update_statement) => Some(&update_statement.table.relation),
         Statement::Delete(delete_statement) => match &delete_statement.from {
             sqlparser::ast::FromTable::WithFromKeyword(table) => table,
             sqlparser::ast::FromTable::WithoutKeyword(table) => table,
         }
         .first()
-        .and_then(|table_with_joins| extract_alias_from_table_factor(&table_with_joins.relation)),
-        Statement::Query(query) => match query.body.as_ref() {
-            SetExpr::Select(select) => select.from.first().and_then(|table_with_joins| {
-                extract_alias_from_table_factor(&table_with_joins.relation)
-            }),
-            _ => None,
-        },
+        .map(|table_with_joins| &table_with_joins.relation),
+        Statement::Query(query) => extract_table_factor_from_query(query),
+        Statement::Merge(merge_statement) => Some(&merge_statement.table),
+        // The batch ranges over the rows being read, so the table comes from the SELECT side.
+        Statement::Insert(insert_statement) => insert_statement
+            .source
+            .as_deref()
+            .and_then(extract_table_factor_from_query),
+        _ => None,
+    }
+}
+
+/// The `WHERE` of the statement, or of the SELECT feeding an `INSERT ... SELECT`.
+fn extract_selection(statement: &Statement) -> Option<&Expr> {
+    match split_cte_wrapped_dml(statement).0 {
+        Statement::Update(update_statement) => update_statement.selection.as_ref(),
+        Statement::Delete(delete_statement) => delete_statement.selection.as_ref(),
+        Statement::Query(query) => extract_selection_from_query(query),
+        Statement::Insert(insert_statement) => insert_statement
+            .source
+            .as_deref()
+            .and_then(extract_selection_from_query),
+        _ => None,
+    }
+}
+
+fn extract_selection_from_query(query: &Query) -> Option<&Expr> {
+    match query.body.as_ref() {
+        SetExpr::Select(select) => select.selection.as_ref(),
+        _ => None,
+    }
+}
+
+fn extract_table_factor_from_query(query: &Query) -> Option<&TableFactor> {
+    match query.body.as_ref() {
+        SetExpr::Select(select) => select
+            .from
+            .first()
+            .map(|table_with_joins| &table_with_joins.relation),
         _ => None,
     }
 }
@@ -142,35 +964,88 @@ fn extract_alias_from_table_factor(table_factor: &TableFactor) -> Option<&str> {
     }
 }
 
-fn inject_batch_condition(statement: &mut Statement, batch_condition: Expr) -> Result<()> {
+fn inject_batch_condition(
+    statement: &mut Statement,
+    batch_condition: Expr,
+) -> Result<(), DomainError> {
     match statement {
+        // A leftover LIMIT would cap every batch and silently skip the rest of its range,
+        // so the id range alone decides which rows a batch touches.
         Statement::Update(update_statement) => {
+            update_statement.limit = None;
             merge_selection(&mut update_statement.selection, batch_condition);
             Ok(())
         }
         Statement::Delete(delete_statement) => {
+            delete_statement.limit = None;
+            delete_statement.order_by.clear();
             merge_selection(&mut delete_statement.selection, batch_condition);
             Ok(())
         }
+        // The condition belongs to the outer UPDATE/DELETE, never to one of its CTEs.
         Statement::Query(query) => match query.body.as_mut() {
-            SetExpr::Select(select) => {
-                merge_selection(&mut select.selection, batch_condition);
-                Ok(())
+            SetExpr::Update(dml_statement) | SetExpr::Delete(dml_statement) => {
+                inject_batch_condition(dml_statement, batch_condition)
+            }
+            _ => inject_into_query(query, batch_condition),
+        },
+        // Narrowing `ON` instead would turn targets outside the batch into `NOT MATCHED` rows and
+        // insert their source rows again, so only the clauses acting on target rows get the
+        // condition; `WHEN NOT MATCHED` inserts are left to whichever batch runs first.
+        Statement::Merge(merge_statement) => {
+            let mut target_clauses = merge_statement
+                .clauses
+                .iter_mut()
+                .filter(|clause| {
+                    matches!(
+                        clause.clause_kind,
+                        MergeClauseKind::Matched | MergeClauseKind::NotMatchedBySource
+                    )
+                })
+                .peekable();
+            if target_clauses.peek().is_none() {
+                return Err(DomainError::UnsupportedStatement(
+                    "MERGE needs a WHEN MATCHED clause to be batched by the target's primary key"
+                        .to_string(),
+                ));
+            }
+            for clause in target_clauses {
+                merge_selection(&mut clause.predicate, batch_condition.clone());
+            }
+            Ok(())
+        }
+        Statement::Insert(insert_statement) => match insert_statement.source.as_deref_mut() {
+            Some(source_query) if matches!(source_query.body.as_ref(), SetExpr::Select(_)) => {
+                inject_into_query(source_query, batch_condition)
             }
-            _ => Err(anyhow!(
-                "Only SELECT statements with direct FROM clause are supported"
+            _ => Err(DomainError::UnsupportedStatement(
+                "Only INSERT ... SELECT statements are supported for INSERT".to_string(),
             )),
         },
-        _ => Err(anyhow!(
-            "Only UPDATE, DELETE and SELECT statements are currently supported"
+        _ => Err(DomainError::UnsupportedStatement(
+            "Only UPDATE, DELETE, MERGE, SELECT and INSERT ... SELECT statements are currently supported"
+                .to_string(),
         )),
     }
 }
 
+fn inject_into_query(query: &mut Query, batch_condition: Expr) -> Result<(), DomainError> {
+    match query.body.as_mut() {
+        SetExpr::Select(select) => {
+            merge_selection(&mut select.selection, batch_condition);
+            Ok(())
+        }
+        _ => Err(DomainError::UnsupportedStatement(
+            "Only SELECT statements with direct FROM clause are supported".to_string(),
+        )),
+    }
+}
+
+/// Both sides are parenthesized when compound, so the result always reads `(batch) AND (original)`.
 fn merge_selection(existing_selection: &mut Option<Expr>, batch_condition: Expr) {
     *existing_selection = Some(match existing_selection.take() {
         Some(previous_condition) => Expr::BinaryOp {
-            left: Box::new(batch_condition),
+            left: Box::new(nest_compound_condition(batch_condition)),
             op: BinaryOperator::And,
             right: Box::new(Expr::Nested(Box::new(previous_condition))),
         },
@@ -178,9 +1053,25 @@ fn merge_selection(existing_selection: &mut Option<Expr>, batch_condition: Expr)
     });
 }
 
+/// Half-open ranges and key prefixes render as `AND` chains; a lone `BETWEEN` or `IN` stays bare.
+fn nest_compound_condition(condition: Expr) -> Expr {
+    match condition {
+        Expr::BinaryOp {
+            op: BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor,
+            ..
+        } => Expr::Nested(Box::new(condition)),
+        condition => condition,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::domain::sql_dialect::SqlDialectKind;
+    use crate::domain::{
+        date_batch::DateBatchRange,
+        error::DomainError,
+        id_batch::{BatchingMode, IdShard, RangeBounds},
+        sql_dialect::SqlDialectKind,
+    };
 
     use super::SqlParserBatchTemplate;
 
@@ -190,6 +1081,10 @@ mod tests {
             "UPDATE users u SET active = 0 WHERE status = 'old'",
             SqlDialectKind::Generic,
             "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
         )
         .expect("template should be parsed");
 
@@ -209,6 +1104,10 @@ mod tests {
             "DELETE FROM users u WHERE u.status = 'old'",
             SqlDialectKind::Generic,
             "users.id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
         )
         .expect("template should be parsed");
 
@@ -228,6 +1127,10 @@ mod tests {
             "SELECT u.id, o.id FROM users u JOIN orders o ON o.user_id = u.id WHERE o.state = 'paid'",
             SqlDialectKind::Generic,
             "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
         )
         .expect("template should be parsed");
 
@@ -243,9 +1146,16 @@ mod tests {
 
     #[test]
     fn adds_where_clause_when_statement_has_no_selection() {
-        let template =
-            SqlParserBatchTemplate::parse("DELETE FROM users", SqlDialectKind::Generic, "id")
-                .expect("template should be parsed");
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
 
         let sql = template
             .render_for_range(1, 10)
@@ -253,4 +1163,1029 @@ mod tests {
 
         assert_eq!(sql, "DELETE FROM users WHERE id BETWEEN 1 AND 10");
     }
+
+    #[test]
+    fn renders_half_open_range_as_two_comparisons() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE users u SET active = 0 WHERE status = 'old'",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::HalfOpen,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(51, 101)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "UPDATE users u SET active = 0 WHERE (u.id >= 51 AND u.id < 101) AND (status = 'old')"
+        );
+    }
+
+    #[test]
+    fn groups_both_sides_when_original_where_uses_or() {
+        let parse = |range_bounds| {
+            SqlParserBatchTemplate::parse(
+                "DELETE FROM users WHERE a = 1 OR b = 2",
+                SqlDialectKind::Generic,
+                "id",
+                &[],
+                range_bounds,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+        };
+
+        assert_eq!(
+            parse(RangeBounds::InclusiveBetween)
+                .render_for_range(1, 50)
+                .expect("sql should be rendered"),
+            "DELETE FROM users WHERE id BETWEEN 1 AND 50 AND (a = 1 OR b = 2)"
+        );
+        assert_eq!(
+            parse(RangeBounds::HalfOpen)
+                .render_for_range(1, 51)
+                .expect("sql should be rendered"),
+            "DELETE FROM users WHERE (id >= 1 AND id < 51) AND (a = 1 OR b = 2)"
+        );
+    }
+
+    #[test]
+    fn renders_explicit_ids_as_in_list() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users u WHERE u.status = 'old'",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_ids(&[3, 8, 21])
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "DELETE FROM users u WHERE u.id IN (3, 8, 21) AND (u.status = 'old')"
+        );
+    }
+
+    #[test]
+    fn appends_order_by_and_limit_in_limit_based_mode() {
+        let delete_template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users WHERE status = 'old'",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect("template should be parsed");
+        let update_template = SqlParserBatchTemplate::parse(
+            "UPDATE users u SET active = 0 WHERE active = 1",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            delete_template
+                .render_with_limit(500)
+                .expect("sql should be rendered"),
+            "DELETE FROM users WHERE status = 'old' ORDER BY id LIMIT 500"
+        );
+        assert_eq!(
+            update_template
+                .render_with_limit(500)
+                .expect("sql should be rendered"),
+            "UPDATE users u SET active = 0 WHERE active = 1 ORDER BY u.id LIMIT 500"
+        );
+    }
+
+    #[test]
+    fn rejects_limit_based_update_on_dialect_without_update_limit() {
+        let error = SqlParserBatchTemplate::parse(
+            "UPDATE users SET active = 0 WHERE active = 1",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect_err("postgres update with limit should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("does not support ORDER BY ... LIMIT on UPDATE")
+        );
+    }
+
+    #[test]
+    fn rejects_limit_based_select() {
+        let error = SqlParserBatchTemplate::parse(
+            "SELECT * FROM users",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect_err("select should be rejected in limit mode");
+
+        assert!(
+            error
+                .to_string()
+                .contains("only supports UPDATE and DELETE")
+        );
+    }
+
+    #[test]
+    fn injects_batch_condition_into_insert_select_source() {
+        let template = SqlParserBatchTemplate::parse(
+            "INSERT INTO t2 SELECT id, name FROM t1 WHERE active = 1",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1, 50)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "INSERT INTO t2 SELECT id, name FROM t1 WHERE id BETWEEN 1 AND 50 AND (active = 1)"
+        );
+    }
+
+    #[test]
+    fn uses_select_alias_instead_of_insert_target_for_insert_select() {
+        let template = SqlParserBatchTemplate::parse(
+            "INSERT INTO archive (id, name) SELECT o.id, o.name FROM orders o WHERE o.state = 'done'",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1, 50)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "INSERT INTO archive (id, name) SELECT o.id, o.name FROM orders o WHERE o.id BETWEEN 1 AND 50 AND (o.state = 'done')"
+        );
+    }
+
+    #[test]
+    fn rejects_insert_with_values_source() {
+        let template = SqlParserBatchTemplate::parse(
+            "INSERT INTO t2 (id) VALUES (1)",
+            SqlDialectKind::Generic,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let error = template
+            .render_for_range(1, 50)
+            .expect_err("insert values should be rejected");
+        assert!(
+            error
+                .to_string()
+                .contains("Only INSERT ... SELECT statements are supported")
+        );
+    }
+
+    #[test]
+    fn strips_limit_and_order_by_from_delete() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM t WHERE x = 1 ORDER BY id LIMIT 10",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1, 100)
+            .expect("sql should be rendered");
+
+        assert_eq!(sql, "DELETE FROM t WHERE id BETWEEN 1 AND 100 AND (x = 1)");
+        assert!(!sql.contains("LIMIT"));
+        assert!(!sql.contains("ORDER BY"));
+    }
+
+    #[test]
+    fn strips_limit_from_update() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE t SET y = 2 WHERE x = 1 LIMIT 10",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1, 100)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "UPDATE t SET y = 2 WHERE id BETWEEN 1 AND 100 AND (x = 1)"
+        );
+        assert!(!sql.contains("LIMIT"));
+    }
+
+    #[test]
+    fn renders_composite_key_as_row_value_comparison() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM orders WHERE state = 'stale'",
+            SqlDialectKind::PostgreSql,
+            "tenant_id, id",
+            &["5".to_string()],
+            RangeBounds::HalfOpen,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1000, 2000)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            sql,
+            "DELETE FROM orders WHERE ((tenant_id, id) >= (5, 1000) AND (tenant_id, id) < (5, 2000)) AND (state = 'stale')"
+        );
+    }
+
+    #[test]
+    fn expands_composite_key_for_dialects_without_row_values() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE o SET state = 'done' FROM orders o",
+            SqlDialectKind::MsSql,
+            "tenant_id,id",
+            &["eu-1".to_string()],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_for_range(1000, 1999)
+            .expect("sql should be rendered");
+
+        assert!(sql.ends_with("WHERE tenant_id = 'eu-1' AND id BETWEEN 1000 AND 1999"));
+    }
+
+    #[test]
+    fn prefixes_each_composite_key_column_with_alias() {
+        let template = SqlParserBatchTemplate::parse(
+            "SELECT * FROM orders o",
+            SqlDialectKind::MySql,
+            "tenant_id,id",
+            &["5".to_string()],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_for_ids(&[7, 9])
+                .expect("sql should be rendered"),
+            "SELECT * FROM orders o WHERE o.tenant_id = 5 AND o.id IN (7, 9)"
+        );
+        assert_eq!(
+            template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered"),
+            "SELECT * FROM orders o WHERE (o.tenant_id, o.id) >= (5, 1) AND (o.tenant_id, o.id) <= (5, 10)"
+        );
+    }
+
+    #[test]
+    fn rejects_composite_key_without_matching_prefix_values() {
+        let error = SqlParserBatchTemplate::parse(
+            "DELETE FROM orders",
+            SqlDialectKind::PostgreSql,
+            "tenant_id,id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect_err("missing key prefix should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("has 1 leading column(s) but 0 key prefix value(s) were given")
+        );
+    }
+
+    #[test]
+    fn quotes_primary_key_with_dialect_quote_style() {
+        for (dialect_kind, expected_sql) in [
+            (
+                SqlDialectKind::MySql,
+                "DELETE FROM orders WHERE `order` BETWEEN 1 AND 10",
+            ),
+            (
+                SqlDialectKind::PostgreSql,
+                "DELETE FROM orders WHERE \"order\" BETWEEN 1 AND 10",
+            ),
+            (
+                SqlDialectKind::Snowflake,
+                "DELETE FROM orders WHERE \"order\" BETWEEN 1 AND 10",
+            ),
+            (
+                SqlDialectKind::Sqlite,
+                "DELETE FROM orders WHERE \"order\" BETWEEN 1 AND 10",
+            ),
+            (
+                SqlDialectKind::MsSql,
+                "DELETE FROM orders WHERE [order] BETWEEN 1 AND 10",
+            ),
+        ] {
+            let template = SqlParserBatchTemplate::parse(
+                "DELETE FROM orders",
+                dialect_kind,
+                "order",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                true,
+            )
+            .expect("template should be parsed");
+
+            let sql = template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered");
+            assert_eq!(sql, expected_sql, "{dialect_kind}");
+        }
+    }
+
+    #[test]
+    fn quotes_qualified_primary_key_parts_but_not_the_alias() {
+        let alias_template = SqlParserBatchTemplate::parse(
+            "SELECT * FROM orders o",
+            SqlDialectKind::PostgreSql,
+            "order",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            true,
+        )
+        .expect("template should be parsed");
+        let qualified_template = SqlParserBatchTemplate::parse(
+            "SELECT * FROM orders",
+            SqlDialectKind::MySql,
+            "orders.order",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            true,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            alias_template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered"),
+            "SELECT * FROM orders o WHERE o.\"order\" BETWEEN 1 AND 10"
+        );
+        assert_eq!(
+            qualified_template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered"),
+            "SELECT * FROM orders WHERE `orders`.`order` BETWEEN 1 AND 10"
+        );
+    }
+
+    #[test]
+    fn renders_min_max_bootstrap_query_for_target_table() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE users u SET active = 0 WHERE status = 'old'",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_range_bootstrap_query()
+                .expect("bootstrap query should be rendered"),
+            "SELECT MIN(u.id), MAX(u.id) FROM users u"
+        );
+    }
+
+    #[test]
+    fn bootstrap_query_fixes_composite_key_prefix() {
+        let template = SqlParserBatchTemplate::parse(
+            "INSERT INTO archive SELECT * FROM orders WHERE state = 'done'",
+            SqlDialectKind::MySql,
+            "tenant_id,id",
+            &["5".to_string()],
+            RangeBounds::HalfOpen,
+            BatchingMode::RangeBased,
+            true,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_range_bootstrap_query()
+                .expect("bootstrap query should be rendered"),
+            "SELECT MIN(`id`), MAX(`id`) FROM orders WHERE `tenant_id` = 5"
+        );
+    }
+
+    #[test]
+    fn renders_count_verification_for_update_and_delete() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users WHERE status = 'old'",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+        assert_eq!(
+            template
+                .render_verification_for_range(1, 50)
+                .expect("verification should be rendered"),
+            Some(
+                "SELECT COUNT(*) FROM users WHERE id BETWEEN 1 AND 50 AND (status = 'old')"
+                    .to_string()
+            )
+        );
+
+        let select_template = SqlParserBatchTemplate::parse(
+            "SELECT * FROM users",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+        assert_eq!(
+            select_template
+                .render_verification_for_ids(&[1, 2])
+                .expect("verification should be rendered"),
+            None
+        );
+    }
+
+    #[test]
+    fn renders_string_ids_as_escaped_literals() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users WHERE status = 'old'",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+        let ids = ["0b5c-41".to_string(), "O'Brien".to_string()];
+
+        let rendered_sql = template
+            .render_for_string_ids(&ids)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            rendered_sql,
+            "DELETE FROM users WHERE id IN ('0b5c-41', 'O''Brien') AND (status = 'old')"
+        );
+        assert!(template.validate_rendered_sql(&rendered_sql).is_ok());
+        assert_eq!(
+            template.render_for_string_ids(&[]),
+            Err(DomainError::EmptyIdBatch)
+        );
+    }
+
+    #[test]
+    fn verification_of_joined_update_selects_target_table_only() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE users u JOIN orders o ON o.user_id = u.id SET u.active = 0 WHERE o.state = 'x'",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_verification_for_ids(&[3, 4])
+                .expect("verification should be rendered"),
+            Some("SELECT COUNT(*) FROM users u WHERE u.id IN (3, 4)".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_shard_condition_with_dialect_modulo_spelling() {
+        let shard = IdShard { index: 2, count: 4 };
+        let render_for_dialect = |dialect_kind| {
+            SqlParserBatchTemplate::parse(
+                "DELETE FROM users WHERE status = 'old'",
+                dialect_kind,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+            .render_for_shard(shard)
+            .expect("sql should be rendered")
+        };
+
+        assert_eq!(
+            render_for_dialect(SqlDialectKind::PostgreSql),
+            "DELETE FROM users WHERE id % 4 = 2 AND (status = 'old')"
+        );
+        assert_eq!(
+            render_for_dialect(SqlDialectKind::Generic),
+            "DELETE FROM users WHERE MOD(id, 4) = 2 AND (status = 'old')"
+        );
+    }
+
+    #[test]
+    fn shards_composite_key_on_its_last_column() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE orders o SET state = 'x'",
+            SqlDialectKind::Snowflake,
+            "tenant_id,id",
+            &["5".to_string()],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_for_shard(IdShard { index: 0, count: 3 })
+                .expect("sql should be rendered"),
+            "UPDATE orders o SET state = 'x' WHERE o.tenant_id = 5 AND MOD(o.id, 3) = 0"
+        );
+    }
+
+    #[test]
+    fn renders_half_open_date_range_as_quoted_literals() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM events e WHERE e.kind = 'click'",
+            SqlDialectKind::PostgreSql,
+            "created_at",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+        let date_range = DateBatchRange {
+            start_date: "2023-01-01".parse().expect("date should parse"),
+            end_date: "2023-02-01".parse().expect("date should parse"),
+        };
+
+        assert_eq!(
+            template
+                .render_for_date_range(date_range)
+                .expect("sql should be rendered"),
+            "DELETE FROM events e WHERE (e.created_at >= '2023-01-01' AND e.created_at < '2023-02-01') AND (e.kind = 'click')"
+        );
+        assert_eq!(
+            template
+                .render_verification_for_date_range(date_range)
+                .expect("verification should be rendered"),
+            Some(
+                "SELECT COUNT(*) FROM events e WHERE (e.created_at >= '2023-01-01' AND e.created_at < '2023-02-01') AND (e.kind = 'click')"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn batches_merge_through_its_matched_clauses() {
+        let template = SqlParserBatchTemplate::parse(
+            "MERGE INTO users t USING staging_users s ON t.id = s.id \
+             WHEN MATCHED AND s.deleted THEN DELETE \
+             WHEN MATCHED THEN UPDATE SET t.name = s.name \
+             WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)",
+            SqlDialectKind::Snowflake,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let rendered_sql = template
+            .render_for_range(1, 100)
+            .expect("sql should be rendered");
+
+        assert_eq!(
+            rendered_sql,
+            "MERGE INTO users t USING staging_users s ON t.id = s.id \
+             WHEN MATCHED AND t.id BETWEEN 1 AND 100 AND (s.deleted) THEN DELETE \
+             WHEN MATCHED AND t.id BETWEEN 1 AND 100 THEN UPDATE SET t.name = s.name \
+             WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)"
+        );
+        assert!(template.validate_rendered_sql(&rendered_sql).is_ok());
+        assert_eq!(
+            template
+                .render_range_bootstrap_query()
+                .expect("bootstrap query should be rendered"),
+            "SELECT MIN(t.id), MAX(t.id) FROM users t"
+        );
+    }
+
+    #[test]
+    fn rejects_merge_without_matched_clause_or_merge_support() {
+        let parse_merge = |raw_sql: &str, dialect_kind| {
+            SqlParserBatchTemplate::parse(
+                raw_sql,
+                dialect_kind,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+        };
+        let insert_only_merge = "MERGE INTO users t USING staging_users s ON t.id = s.id \
+                                 WHEN NOT MATCHED THEN INSERT (id) VALUES (s.id)";
+
+        assert!(matches!(
+            parse_merge(insert_only_merge, SqlDialectKind::Generic),
+            Err(DomainError::UnsupportedStatement(message)) if message.contains("does not support MERGE")
+        ));
+        assert!(matches!(
+            parse_merge(insert_only_merge, SqlDialectKind::MsSql)
+                .expect("template should be parsed")
+                .render_for_range(1, 10),
+            Err(DomainError::UnsupportedStatement(message)) if message.contains("WHEN MATCHED")
+        ));
+    }
+
+    #[test]
+    fn finds_ranged_key_of_update_or_delete_without_where() {
+        let find_key = |raw_sql: &str| {
+            SqlParserBatchTemplate::parse(
+                raw_sql,
+                SqlDialectKind::PostgreSql,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+            .find_unfiltered_dml_key()
+        };
+
+        assert_eq!(find_key("DELETE FROM users u"), Some("u.id".to_string()));
+        assert_eq!(
+            find_key("WITH stale AS (SELECT 1) UPDATE users SET active = 0"),
+            Some("id".to_string())
+        );
+        assert_eq!(find_key("DELETE FROM users WHERE status = 'old'"), None);
+        assert_eq!(find_key("SELECT * FROM users"), None);
+    }
+
+    #[test]
+    fn finds_existing_primary_key_predicate_in_where_clause() {
+        let find_predicate = |raw_sql: &str| {
+            SqlParserBatchTemplate::parse(
+                raw_sql,
+                SqlDialectKind::MySql,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+            .find_id_column_predicate()
+        };
+
+        assert_eq!(
+            find_predicate("DELETE FROM users WHERE status = 'old' AND (id BETWEEN 1 AND 1000)"),
+            Some("id BETWEEN 1 AND 1000".to_string())
+        );
+        assert_eq!(
+            find_predicate("UPDATE users u SET active = 0 WHERE u.ID >= 10 OR active = 1"),
+            Some("u.ID >= 10".to_string())
+        );
+        assert_eq!(
+            find_predicate("DELETE FROM users WHERE users.id < 5"),
+            Some("users.id < 5".to_string())
+        );
+        assert_eq!(
+            find_predicate(
+                "UPDATE users u JOIN orders o ON o.user_id = u.id SET u.active = 0 WHERE o.id > 5"
+            ),
+            None
+        );
+        assert_eq!(find_predicate("DELETE FROM users WHERE user_id = 5"), None);
+    }
+
+    #[test]
+    fn injects_batch_condition_into_outer_delete_after_cte() {
+        let template = SqlParserBatchTemplate::parse(
+            "WITH stale AS (SELECT id FROM orders WHERE state = 'x') DELETE FROM orders o WHERE o.id IN (SELECT id FROM stale)",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered"),
+            "WITH stale AS (SELECT id FROM orders WHERE state = 'x') DELETE FROM orders o WHERE o.id BETWEEN 1 AND 10 AND (o.id IN (SELECT id FROM stale))"
+        );
+        assert_eq!(
+            template
+                .render_verification_for_range(1, 10)
+                .expect("verification should be rendered"),
+            Some("WITH stale AS (SELECT id FROM orders WHERE state = 'x') SELECT COUNT(*) FROM orders o WHERE o.id BETWEEN 1 AND 10 AND (o.id IN (SELECT id FROM stale))".to_string())
+        );
+    }
+
+    #[test]
+    fn resolves_outer_update_alias_after_cte() {
+        let template = SqlParserBatchTemplate::parse(
+            "WITH stale AS (SELECT user_id FROM sessions s) UPDATE users u SET active = 0 WHERE u.id IN (SELECT user_id FROM stale)",
+            SqlDialectKind::PostgreSql,
+            "id",
+            &[],
+            RangeBounds::HalfOpen,
+            BatchingMode::RangeBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        assert_eq!(
+            template
+                .render_for_range(1, 10)
+                .expect("sql should be rendered"),
+            "WITH stale AS (SELECT user_id FROM sessions s) UPDATE users u SET active = 0 WHERE (u.id >= 1 AND u.id < 10) AND (u.id IN (SELECT user_id FROM stale))"
+        );
+    }
+
+    #[test]
+    fn validates_rendered_sql_by_reparsing_it() {
+        let template = SqlParserBatchTemplate::parse(
+            "DELETE FROM users",
+            SqlDialectKind::MsSql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::RangeBased,
+            true,
+        )
+        .expect("template should be parsed");
+        let rendered_sql = template
+            .render_for_range(1, 10)
+            .expect("sql should be rendered");
+
+        assert_eq!(template.validate_rendered_sql(&rendered_sql), Ok(()));
+        let limit_template = SqlParserBatchTemplate::parse(
+            "UPDATE users SET active = 0",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect("template should be parsed");
+        let limited_sql = limit_template
+            .render_with_limit(100)
+            .expect("sql should be rendered");
+        assert_eq!(limit_template.validate_rendered_sql(&limited_sql), Ok(()));
+        assert!(matches!(
+            template.validate_rendered_sql("DELETE FROM users WHERE [id BETWEEN 1 AND 10"),
+            Err(DomainError::ParseFailed(_))
+        ));
+    }
+
+    #[test]
+    fn joined_verification_keeps_target_conditions_and_flags_dropped_ones() {
+        let parse = |raw_sql| {
+            SqlParserBatchTemplate::parse(
+                raw_sql,
+                SqlDialectKind::MySql,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+        };
+
+        let template = parse(
+            "UPDATE users u JOIN orders o ON o.user_id = u.id SET u.active = 0 WHERE u.status = 'old' AND o.state = 'x' AND (u.age > 3 AND u.age < 9)",
+        );
+        assert!(template.verification_is_approximate());
+        assert_eq!(
+            template
+                .render_verification_for_range(1, 50)
+                .expect("verification should be rendered"),
+            Some(
+                "SELECT COUNT(*) FROM users u WHERE u.id BETWEEN 1 AND 50 AND (u.status = 'old' AND u.age > 3 AND u.age < 9)"
+                    .to_string()
+            )
+        );
+
+        let template = parse(
+            "UPDATE users u JOIN orders o ON o.user_id = u.id SET u.active = 0 WHERE u.status IN ('old', 'idle')",
+        );
+        assert!(!template.verification_is_approximate());
+        assert!(!parse("DELETE FROM users WHERE status = 'old'").verification_is_approximate());
+    }
+
+    #[test]
+    fn casts_bounds_beyond_bigint_only_where_the_dialect_needs_it() {
+        let render_for_dialect = |dialect_kind, start_id, end_id| {
+            SqlParserBatchTemplate::parse(
+                "DELETE FROM events",
+                dialect_kind,
+                "id",
+                &[],
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+            .expect("template should be parsed")
+            .render_for_range(start_id, end_id)
+            .expect("sql should be rendered")
+        };
+        let bigint_max = i128::from(i64::MAX);
+
+        assert_eq!(
+            render_for_dialect(SqlDialectKind::MsSql, bigint_max - 1, bigint_max),
+            "DELETE FROM events WHERE id BETWEEN 9223372036854775806 AND 9223372036854775807"
+        );
+        assert_eq!(
+            render_for_dialect(SqlDialectKind::MsSql, bigint_max, bigint_max + 1),
+            "DELETE FROM events WHERE id BETWEEN 9223372036854775807 AND CAST('9223372036854775808' AS DECIMAL(38,0))"
+        );
+        assert_eq!(
+            render_for_dialect(
+                SqlDialectKind::MsSql,
+                i128::from(i64::MIN) - 1,
+                i128::from(i64::MIN)
+            ),
+            "DELETE FROM events WHERE id BETWEEN CAST('-9223372036854775809' AS DECIMAL(38,0)) AND -9223372036854775808"
+        );
+        assert_eq!(
+            render_for_dialect(SqlDialectKind::PostgreSql, bigint_max, bigint_max + 1),
+            "DELETE FROM events WHERE id BETWEEN 9223372036854775807 AND 9223372036854775808"
+        );
+    }
+
+    #[test]
+    fn rejects_ids_without_an_exact_literal_on_the_dialect() {
+        let parse = |dialect_kind, range_column, key_prefix: &[String]| {
+            SqlParserBatchTemplate::parse(
+                "DELETE FROM events",
+                dialect_kind,
+                range_column,
+                key_prefix,
+                RangeBounds::InclusiveBetween,
+                BatchingMode::RangeBased,
+                false,
+            )
+        };
+        let largest_decimal = 10i128.pow(38) - 1;
+        let mssql = parse(SqlDialectKind::MsSql, "id", &[]).expect("template should be parsed");
+
+        assert_eq!(
+            mssql
+                .render_for_range(largest_decimal - 1, largest_decimal)
+                .expect("sql should be rendered"),
+            "DELETE FROM events WHERE id BETWEEN CAST('99999999999999999999999999999999999998' AS DECIMAL(38,0)) AND CAST('99999999999999999999999999999999999999' AS DECIMAL(38,0))"
+        );
+        assert_eq!(
+            mssql.render_for_range(largest_decimal, i128::MAX),
+            Err(DomainError::UnrepresentableId {
+                id: i128::MAX,
+                dialect_kind: SqlDialectKind::MsSql,
+                reason: "DECIMAL(38,0) holds at most 38 digits",
+            })
+        );
+        assert!(mssql.render_for_ids(&[i128::MIN]).is_err());
+
+        let sqlite = parse(SqlDialectKind::Sqlite, "id", &[]).expect("template should be parsed");
+        let bigint_max = i128::from(i64::MAX);
+        assert!(sqlite.render_for_range(1, bigint_max).is_ok());
+        assert!(matches!(
+            sqlite.render_for_range(1, bigint_max + 1),
+            Err(DomainError::UnrepresentableId {
+                dialect_kind: SqlDialectKind::Sqlite,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(
+                SqlDialectKind::Sqlite,
+                "tenant_id,id",
+                &[i128::MAX.to_string()]
+            ),
+            Err(DomainError::UnrepresentableId { .. })
+        ));
+    }
+
+    #[test]
+    fn validates_the_limit_based_update_in_front_of_its_order_by() {
+        let template = SqlParserBatchTemplate::parse(
+            "UPDATE users SET active = 0 WHERE active = 1 LIMIT 10",
+            SqlDialectKind::MySql,
+            "id",
+            &[],
+            RangeBounds::InclusiveBetween,
+            BatchingMode::LimitBased,
+            false,
+        )
+        .expect("template should be parsed");
+
+        let sql = template
+            .render_with_limit(500)
+            .expect("sql should be rendered");
+        assert_eq!(
+            sql,
+            "UPDATE users SET active = 0 WHERE active = 1 ORDER BY id LIMIT 500"
+        );
+        assert_eq!(template.validate_rendered_sql(&sql), Ok(()));
+        assert!(
+            template
+                .validate_rendered_sql("UPDATE users SET WHERE active = 1 ORDER BY id LIMIT 500")
+                .is_err()
+        );
+        assert!(
+            template
+                .validate_rendered_sql(
+                    "UPDATE users SET active = 0 WHERE active = 1 ORDER BY id LIMIT 5 0"
+                )
+                .is_err()
+        );
+    }
 }
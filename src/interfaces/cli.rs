@@ -1,47 +1,222 @@
 use std::{
     env, fs,
+    io::{self, Read},
     path::{Path, PathBuf},
 };
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
 use clap::{Parser, ValueEnum};
 use console::style;
-use dialoguer::{Editor, Input, Select, theme::ColorfulTheme};
+use dialoguer::{Confirm, Editor, Input, Select, theme::ColorfulTheme};
 
-use crate::{
-    application::commands::GenerateBatchedSqlCommand, domain::sql_dialect::SqlDialectKind,
+use sql_id_slicer::{
+    BatchValidation, BatchingMode, DateRange, DateStep, GenerateBatchedSqlCommand, OutputFormat,
+    RangeBounds, SqlDialectKind, StatementTerminator, TransactionMode, count_id_range_batches,
+    read_id_file, read_string_id_file,
 };
 
+use crate::interfaces::job_file::{JobSpec, read_job_file};
+
 const DEFAULT_BATCH_SIZE: usize = 10_000;
 const DEFAULT_SLEEP_SECONDS: u64 = 1;
 const DEFAULT_OUTPUT: &str = "id_slice.sql";
-const DEFAULT_PRIMARY_KEY: &str = "id";
+const DEFAULT_RANGE_COLUMN: &str = "id";
+const STDOUT_OUTPUT: &str = "-";
+const STDIN_SQL_FILE: &str = "-";
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 #[command(
     name = "sql-id-slicer",
     version,
     about = "Split one SQL into multiple primary-key-based batches"
 )]
 struct CliArgs {
-    #[arg(long, short = 's')]
+    #[arg(
+        long,
+        conflicts_with_all = ["start_id", "end_id", "id_file", "shards", "sql", "sql_file", "output", "stdout"],
+        help = "Run every [[jobs]] entry of a TOML file; other flags apply to all jobs"
+    )]
+    jobs: Option<PathBuf>,
+    #[arg(long, short = 's', conflicts_with = "id_file")]
     start_id: Option<i128>,
-    #[arg(long, short = 'e')]
+    #[arg(long, short = 'e', conflicts_with = "id_file")]
     end_id: Option<i128>,
+    #[arg(
+        long,
+        help = "Read explicit ids (one per line) instead of a start/end range"
+    )]
+    id_file: Option<PathBuf>,
+    #[arg(
+        long,
+        requires = "id_file",
+        help = "Treat --id-file entries as text, such as UUIDs, and batch them as quoted literals"
+    )]
+    string_ids: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["start_id", "end_id", "id_file"],
+        help = "Emit one statement per shard with `pk % N = i` instead of id ranges"
+    )]
+    shards: Option<usize>,
+    #[arg(
+        long,
+        requires = "end_date",
+        conflicts_with_all = ["start_id", "end_id", "id_file", "shards", "jobs"],
+        help = "Slice the --range-column as dates from this day (YYYY-MM-DD), inclusive"
+    )]
+    start_date: Option<NaiveDate>,
+    #[arg(
+        long,
+        requires = "start_date",
+        help = "Last date boundary (YYYY-MM-DD), exclusive"
+    )]
+    end_date: Option<NaiveDate>,
+    #[arg(
+        long,
+        default_value = "1d",
+        help = "Width of each date batch: <n>d days, <n>w weeks, <n>M months or <n>y years"
+    )]
+    step: DateStep,
     #[arg(long, short = 'b', default_value_t = DEFAULT_BATCH_SIZE)]
     batch_size: usize,
-    #[arg(long, short = 't', default_value_t = DEFAULT_SLEEP_SECONDS)]
+    #[arg(
+        long,
+        short = 't',
+        visible_alias = "sleep-between",
+        default_value_t = DEFAULT_SLEEP_SECONDS,
+        help = "Seconds to pause between batches; 0 disables the pause"
+    )]
     sleep_seconds: u64,
     #[arg(long, short = 'q', help = "Raw SQL text")]
     sql: Option<String>,
-    #[arg(long, short = 'f', help = "Read SQL from file path")]
+    #[arg(
+        long,
+        short = 'f',
+        help = "Read SQL from file path; `-` reads it from stdin"
+    )]
     sql_file: Option<PathBuf>,
     #[arg(long, short = 'o', default_value = DEFAULT_OUTPUT)]
     output: PathBuf,
-    #[arg(long, short = 'k', default_value = DEFAULT_PRIMARY_KEY)]
-    primary_key: String,
+    #[arg(
+        long,
+        help = "Write the batches to stdout instead of a file; `--output -` does the same"
+    )]
+    stdout: bool,
+    #[arg(
+        long,
+        help = "Write at most N batch statements per numbered output file"
+    )]
+    split_every: Option<usize>,
+    #[arg(
+        long,
+        short = 'k',
+        visible_alias = "primary-key",
+        default_value = DEFAULT_RANGE_COLUMN,
+        help = "Column to range over, usually the primary key, or comma-separated columns of a composite key ranged on the last one"
+    )]
+    range_column: String,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Fixed values for the leading columns of a composite primary key"
+    )]
+    key_prefix: Vec<String>,
+    #[arg(
+        long,
+        visible_alias = "strict-pk",
+        help = "Fail instead of warning when the SQL's WHERE already compares the range column"
+    )]
+    strict_id_column: bool,
+    #[arg(
+        long,
+        help = "Do not print warnings about the input SQL, such as an UPDATE/DELETE without WHERE"
+    )]
+    no_warn: bool,
+    #[arg(
+        long,
+        help = "Quote primary key identifiers for the dialect (`id`, \"id\" or [id])"
+    )]
+    quote_identifiers: bool,
     #[arg(long, short = 'd', value_enum, default_value_t = CliDialect::Generic)]
     dialect: CliDialect,
+    #[arg(
+        long,
+        default_value = "none",
+        help = "Wrap batches in transactions: none, per-batch or every-<N>"
+    )]
+    transaction: TransactionMode,
+    #[arg(
+        long,
+        default_value = "semicolon",
+        help = "End each statement with: semicolon, go (a GO line for sqlcmd) or custom text such as ';\\n'"
+    )]
+    terminator: StatementTerminator,
+    #[arg(long, value_enum, default_value_t = CliRangeBounds::Between)]
+    range_bounds: CliRangeBounds,
+    #[arg(
+        long,
+        default_value_t = 0,
+        allow_negative_numbers = true,
+        help = "Extend each id range N ids into the next one; a negative N leaves a gap instead"
+    )]
+    overlap: i128,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = CliBatchingMode::Range,
+        help = "range: one statement per id range; limit: one ORDER BY pk LIMIT batch_size statement to re-run"
+    )]
+    batching: CliBatchingMode,
+    #[arg(
+        long,
+        help = "Emit batches from the highest ids down; each batch covers the same ids"
+    )]
+    descending: bool,
+    #[arg(
+        long,
+        help = "Prefix each batch with a `-- batch i/n | id a..b` comment"
+    )]
+    annotate: bool,
+    #[arg(
+        long,
+        value_name = "K",
+        help = "Write only the first K batches, to try a large run on a small slice first"
+    )]
+    limit_batches: Option<usize>,
+    #[arg(
+        long,
+        help = "Print the batch count and the first and last statement without writing any file"
+    )]
+    dry_run: bool,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = CliOutputFormat::Sql,
+        help = "sql: one statement per line; json: an array of {index, start_id, end_id, sql} records"
+    )]
+    format: CliOutputFormat,
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "first-and-last",
+        help = "Re-parse rendered batches before writing them: first-and-last (the default when no value is given) or all"
+    )]
+    validate: Option<CliValidation>,
+    #[arg(
+        long,
+        help = "Precede each UPDATE/DELETE batch with a commented-out SELECT COUNT(*) over the same rows"
+    )]
+    with_verify: bool,
+    #[arg(long, help = "Append to the output file instead of overwriting it")]
+    append: bool,
+    #[arg(
+        long,
+        conflicts_with = "jobs",
+        help = "On success, print a one-line JSON summary to stderr and nothing else there"
+    )]
+    summary_json: bool,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -69,23 +244,165 @@ impl From<CliDialect> for SqlDialectKind {
     }
 }
 
-pub fn collect_generate_command() -> Result<GenerateBatchedSqlCommand> {
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliRangeBounds {
+    Between,
+    HalfOpen,
+}
+
+impl From<CliRangeBounds> for RangeBounds {
+    fn from(value: CliRangeBounds) -> Self {
+        match value {
+            CliRangeBounds::Between => RangeBounds::InclusiveBetween,
+            CliRangeBounds::HalfOpen => RangeBounds::HalfOpen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliBatchingMode {
+    Range,
+    Limit,
+}
+
+impl From<CliBatchingMode> for BatchingMode {
+    fn from(value: CliBatchingMode) -> Self {
+        match value {
+            CliBatchingMode::Range => BatchingMode::RangeBased,
+            CliBatchingMode::Limit => BatchingMode::LimitBased,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliOutputFormat {
+    Sql,
+    Json,
+}
+
+impl From<CliOutputFormat> for OutputFormat {
+    fn from(value: CliOutputFormat) -> Self {
+        match value {
+            CliOutputFormat::Sql => OutputFormat::Sql,
+            CliOutputFormat::Json => OutputFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CliValidation {
+    FirstAndLast,
+    All,
+}
+
+impl From<CliValidation> for BatchValidation {
+    fn from(value: CliValidation) -> Self {
+        match value {
+            CliValidation::FirstAndLast => BatchValidation::FirstAndLast,
+            CliValidation::All => BatchValidation::All,
+        }
+    }
+}
+
+pub enum CliRequest {
+    /// `summary_json` asks for a one-line JSON summary on stderr instead of the usual messages.
+    Generate {
+        command: GenerateBatchedSqlCommand,
+        summary_json: bool,
+    },
+    /// `--start-id` and `--end-id` were both omitted, so only the MIN/MAX query that finds them is printed.
+    BootstrapRange(GenerateBatchedSqlCommand),
+    /// The interactive batch-count preview was declined; nothing should be written.
+    Cancelled,
+    /// One command per `--jobs` entry, in file order.
+    Jobs(Vec<GenerateBatchedSqlCommand>),
+}
+
+pub fn collect_cli_request() -> Result<CliRequest> {
     if env::args_os().len() == 1 {
-        return collect_interactive_command();
+        return Ok(match collect_interactive_command()? {
+            Some(command) => CliRequest::Generate {
+                command,
+                summary_json: false,
+            },
+            None => CliRequest::Cancelled,
+        });
     }
-    collect_command_from_args(CliArgs::parse())
+    collect_request_from_args(CliArgs::parse())
+}
+
+fn collect_request_from_args(args: CliArgs) -> Result<CliRequest> {
+    if let Some(job_file) = &args.jobs {
+        let job_specs = read_job_file(job_file)?;
+        return collect_job_commands(&args, job_specs).map(CliRequest::Jobs);
+    }
+
+    let needs_range_bootstrap = args.id_file.is_none()
+        && args.shards.is_none()
+        && args.start_date.is_none()
+        && BatchingMode::from(args.batching) == BatchingMode::RangeBased
+        && args.start_id.is_none()
+        && args.end_id.is_none();
+    let summary_json = args.summary_json;
+    let command = collect_command_from_args(args)?;
+    Ok(if needs_range_bootstrap {
+        CliRequest::BootstrapRange(command)
+    } else {
+        CliRequest::Generate {
+            command,
+            summary_json,
+        }
+    })
 }
 
 fn collect_command_from_args(args: CliArgs) -> Result<GenerateBatchedSqlCommand> {
-    let start_id = args
-        .start_id
-        .ok_or_else(|| anyhow!("--start-id is required when using argument mode"))?;
-    let end_id = args
-        .end_id
-        .ok_or_else(|| anyhow!("--end-id is required when using argument mode"))?;
+    let batching_mode = BatchingMode::from(args.batching);
+    let mut string_id_list = None;
+    let (start_id, end_id, id_list) = match &args.id_file {
+        // String ids have no numeric bounds to report.
+        Some(id_file) if args.string_ids => {
+            string_id_list = Some(read_string_id_file(id_file)?);
+            (0, 0, None)
+        }
+        Some(id_file) => {
+            let ids = read_id_file(id_file)?;
+            let start_id = ids.iter().copied().min().unwrap_or_default();
+            let end_id = ids.iter().copied().max().unwrap_or_default();
+            (start_id, end_id, Some(ids))
+        }
+        // LIMIT-based batching re-runs one statement, so it has no id range to slice.
+        None if batching_mode == BatchingMode::LimitBased => (
+            args.start_id.unwrap_or_default(),
+            args.end_id.unwrap_or_default(),
+            None,
+        ),
+        // Shards select ids by residue, so they have no id range either.
+        None if args.shards.is_some() => (0, 0, None),
+        // Date batches bound the column by dates, not ids.
+        None if args.start_date.is_some() => (0, 0, None),
+        // The range is unknown yet; `collect_request_from_args` turns this into a bootstrap run.
+        None if args.start_id.is_none() && args.end_id.is_none() => (0, 0, None),
+        None => {
+            let start_id = args
+                .start_id
+                .ok_or_else(|| anyhow!("--start-id is required when using argument mode"))?;
+            let end_id = args
+                .end_id
+                .ok_or_else(|| anyhow!("--end-id is required when using argument mode"))?;
+            (start_id, end_id, None)
+        }
+    };
 
+    let date_range = args
+        .start_date
+        .zip(args.end_date)
+        .map(|(start_date, end_date)| DateRange {
+            start_date,
+            end_date,
+            step: args.step,
+        });
     let raw_sql = read_sql_from_sources(args.sql, args.sql_file)?;
-    let primary_key = ensure_non_empty_value(args.primary_key, "Primary key")?;
+    let range_column = ensure_non_empty_value(args.range_column, "Range column")?;
 
     Ok(GenerateBatchedSqlCommand {
         start_id,
@@ -93,13 +410,86 @@ fn collect_command_from_args(args: CliArgs) -> Result<GenerateBatchedSqlCommand>
         batch_size: args.batch_size,
         sleep_seconds: args.sleep_seconds,
         raw_sql,
+        write_to_stdout: args.stdout || args.output == Path::new(STDOUT_OUTPUT),
         output_path: args.output,
-        primary_key,
+        range_column,
+        key_prefix: args.key_prefix,
+        strict_id_column: args.strict_id_column,
+        // The summary line must be the only thing on stderr.
+        suppress_warnings: args.no_warn || args.summary_json,
+        quote_identifiers: args.quote_identifiers,
         dialect_kind: args.dialect.into(),
+        transaction_mode: args.transaction,
+        statement_terminator: args.terminator,
+        range_bounds: args.range_bounds.into(),
+        overlap: args.overlap,
+        id_list,
+        string_id_list,
+        shard_count: args.shards,
+        date_range,
+        split_every: args.split_every,
+        batching_mode,
+        descending: args.descending,
+        annotate: args.annotate,
+        batch_limit: args.limit_batches,
+        dry_run: args.dry_run,
+        output_format: args.format.into(),
+        validation: args
+            .validate
+            .map_or(BatchValidation::Off, BatchValidation::from),
+        with_verify: args.with_verify,
+        append: args.append,
     })
 }
 
-fn collect_interactive_command() -> Result<GenerateBatchedSqlCommand> {
+/// Applies each job's fields on top of the shared flags and builds its command like a single run.
+fn collect_job_commands(
+    args: &CliArgs,
+    job_specs: Vec<JobSpec>,
+) -> Result<Vec<GenerateBatchedSqlCommand>> {
+    job_specs
+        .into_iter()
+        .enumerate()
+        .map(|(job_index, job_spec)| {
+            collect_job_command(args.clone(), job_spec)
+                .with_context(|| format!("Job {} is invalid", job_index + 1))
+        })
+        .collect()
+}
+
+fn collect_job_command(mut args: CliArgs, job_spec: JobSpec) -> Result<GenerateBatchedSqlCommand> {
+    args.sql = job_spec.raw_sql;
+    args.sql_file = job_spec.sql_file;
+    args.start_id = Some(job_spec.start_id.into());
+    args.end_id = Some(job_spec.end_id.into());
+    args.output = job_spec.output;
+    if let Some(range_column) = job_spec.range_column {
+        args.range_column = range_column;
+    }
+    if let Some(batch_size) = job_spec.batch_size {
+        args.batch_size = batch_size;
+    }
+    // Parsed like a dialect anywhere else, so aliases such as `postgresql` work in job files too.
+    let dialect_kind = job_spec
+        .dialect
+        .as_deref()
+        .map(str::parse::<SqlDialectKind>)
+        .transpose()?;
+
+    let mut command = collect_command_from_args(args)?;
+    if let Some(dialect_kind) = dialect_kind {
+        command.dialect_kind = dialect_kind;
+    }
+    if command.write_to_stdout {
+        return Err(anyhow!(
+            "Jobs cannot write to stdout; give each job its own output file"
+        ));
+    }
+    Ok(command)
+}
+
+/// Returns `None` when the batch-count preview is declined.
+fn collect_interactive_command() -> Result<Option<GenerateBatchedSqlCommand>> {
     let theme = ColorfulTheme::default();
 
     println!();
@@ -141,17 +531,29 @@ fn collect_interactive_command() -> Result<GenerateBatchedSqlCommand> {
         })
         .interact_text()?;
 
+    let batch_count = count_id_range_batches(start_id, end_id, batch_size);
+    let proceed = Confirm::with_theme(&theme)
+        .with_prompt(format!(
+            "This will generate {} batches, proceed?",
+            format_with_thousands_separators(batch_count)
+        ))
+        .default(false)
+        .interact()?;
+    if !proceed {
+        return Ok(None);
+    }
+
     let sleep_seconds: u64 = Input::with_theme(&theme)
         .with_prompt("Sleep seconds between batches (0 to disable)")
         .default(DEFAULT_SLEEP_SECONDS)
         .interact_text()?;
 
-    let primary_key: String = Input::with_theme(&theme)
-        .with_prompt("Primary key column (supports table.column)")
-        .default(DEFAULT_PRIMARY_KEY.to_string())
+    let range_column: String = Input::with_theme(&theme)
+        .with_prompt("Range column, usually the primary key (supports table.column)")
+        .default(DEFAULT_RANGE_COLUMN.to_string())
         .validate_with(|value: &String| {
             if value.trim().is_empty() {
-                Err("Primary key must not be empty")
+                Err("Range column must not be empty")
             } else {
                 Ok(())
             }
@@ -194,22 +596,60 @@ fn collect_interactive_command() -> Result<GenerateBatchedSqlCommand> {
         .default(DEFAULT_OUTPUT.to_string())
         .interact_text()?;
 
-    Ok(GenerateBatchedSqlCommand {
+    Ok(Some(GenerateBatchedSqlCommand {
         start_id,
         end_id,
         batch_size,
         sleep_seconds,
         raw_sql,
+        write_to_stdout: output_name.trim() == STDOUT_OUTPUT,
         output_path: PathBuf::from(output_name.trim()),
-        primary_key: primary_key.trim().to_string(),
+        range_column: range_column.trim().to_string(),
+        key_prefix: Vec::new(),
+        strict_id_column: false,
+        suppress_warnings: false,
+        quote_identifiers: false,
         dialect_kind,
-    })
+        transaction_mode: TransactionMode::None,
+        statement_terminator: StatementTerminator::Semicolon,
+        range_bounds: RangeBounds::InclusiveBetween,
+        overlap: 0,
+        id_list: None,
+        string_id_list: None,
+        shard_count: None,
+        date_range: None,
+        split_every: None,
+        batching_mode: BatchingMode::RangeBased,
+        descending: false,
+        annotate: false,
+        batch_limit: None,
+        dry_run: false,
+        output_format: OutputFormat::Sql,
+        validation: BatchValidation::Off,
+        with_verify: false,
+        append: false,
+    }))
+}
+
+fn format_with_thousands_separators(value: usize) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
 }
 
 fn read_sql_from_sources(sql: Option<String>, sql_file: Option<PathBuf>) -> Result<String> {
     match (sql, sql_file) {
         (Some(_), Some(_)) => Err(anyhow!("Please provide only one of --sql or --sql-file")),
         (Some(sql_text), None) => ensure_non_empty_value(sql_text, "Input SQL"),
+        (None, Some(file_path)) if file_path == Path::new(STDIN_SQL_FILE) => {
+            read_sql_from_reader(io::stdin().lock())
+        }
         (None, Some(file_path)) => read_sql_file(&file_path),
         (None, None) => Err(anyhow!(
             "One of --sql or --sql-file is required when using argument mode"
@@ -223,6 +663,14 @@ fn read_sql_file(path: &Path) -> Result<String> {
     ensure_non_empty_value(content, "Input SQL")
 }
 
+fn read_sql_from_reader(mut reader: impl Read) -> Result<String> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .map_err(|error| anyhow!("Unable to read SQL from stdin: {error}"))?;
+    ensure_non_empty_value(content, "Input SQL")
+}
+
 fn ensure_non_empty_value(value: String, field_name: &str) -> Result<String> {
     if value.trim().is_empty() {
         return Err(anyhow!("{field_name} must not be empty"));
@@ -240,7 +688,15 @@ mod tests {
 
     use clap::Parser;
 
-    use super::{CliArgs, DEFAULT_BATCH_SIZE, DEFAULT_SLEEP_SECONDS, collect_command_from_args};
+    use sql_id_slicer::{
+        BatchValidation, BatchingMode, DateRange, DateStep, OutputFormat, RangeBounds,
+        SqlDialectKind, TransactionMode,
+    };
+
+    use super::{
+        CliArgs, CliRequest, DEFAULT_BATCH_SIZE, DEFAULT_SLEEP_SECONDS, collect_command_from_args,
+        collect_request_from_args, format_with_thousands_separators, read_sql_from_reader,
+    };
 
     fn build_temp_sql_file(content: &str) -> PathBuf {
         let unique_suffix = SystemTime::now()
@@ -276,11 +732,492 @@ mod tests {
         assert_eq!(command.start_id, 1);
         assert_eq!(command.end_id, 10);
         assert_eq!(command.raw_sql, "DELETE FROM users");
-        assert_eq!(command.primary_key, "u.user_id");
+        assert_eq!(command.range_column, "u.user_id");
         assert_eq!(command.batch_size, DEFAULT_BATCH_SIZE);
         assert_eq!(command.sleep_seconds, DEFAULT_SLEEP_SECONDS);
         assert_eq!(command.output_path, PathBuf::from("out.sql"));
         assert_eq!(command.dialect_kind.as_str(), "postgres");
+        assert_eq!(command.transaction_mode, TransactionMode::None);
+        assert_eq!(command.split_every, None);
+    }
+
+    #[test]
+    fn range_column_and_primary_key_set_the_same_column() {
+        let parse_column = |flag: &str| {
+            let args = CliArgs::try_parse_from([
+                "sql-id-slicer",
+                "--start-id",
+                "1",
+                "--end-id",
+                "10",
+                "--sql",
+                "DELETE FROM events",
+                flag,
+                "e.seq",
+            ])
+            .expect("cli args should parse");
+            collect_command_from_args(args)
+                .expect("command should be created")
+                .range_column
+        };
+
+        assert_eq!(parse_column("--range-column"), "e.seq");
+        assert_eq!(parse_column("--primary-key"), "e.seq");
+        assert_eq!(parse_column("-k"), "e.seq");
+    }
+
+    #[test]
+    fn parses_composite_primary_key_with_key_prefix() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1000",
+            "--end-id",
+            "1999",
+            "--sql",
+            "DELETE FROM orders",
+            "--primary-key",
+            "tenant_id,id",
+            "--key-prefix",
+            "5",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.range_column, "tenant_id,id");
+        assert_eq!(command.key_prefix, vec!["5".to_string()]);
+    }
+
+    #[test]
+    fn treats_dash_output_as_stdout() {
+        for output_args in [&["--stdout"][..], &["--output", "-"][..]] {
+            let args = CliArgs::try_parse_from(
+                [
+                    "sql-id-slicer",
+                    "--start-id",
+                    "1",
+                    "--end-id",
+                    "10",
+                    "--sql",
+                    "DELETE FROM users",
+                ]
+                .into_iter()
+                .chain(output_args.iter().copied()),
+            )
+            .expect("cli args should parse");
+
+            let command = collect_command_from_args(args).expect("command should be created");
+            assert!(command.write_to_stdout, "{output_args:?}");
+        }
+    }
+
+    #[test]
+    fn parses_json_output_format() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--format",
+            "json",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.output_format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn parses_limit_batches_flag() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--limit-batches",
+            "3",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.batch_limit, Some(3));
+    }
+
+    #[test]
+    fn parses_dry_run_flag() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--dry-run",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert!(command.dry_run);
+    }
+
+    #[test]
+    fn parses_transaction_mode_in_args_mode() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--transaction",
+            "every-3",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.transaction_mode, TransactionMode::EveryN(3));
+    }
+
+    #[test]
+    fn parses_half_open_range_bounds_in_args_mode() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--range-bounds",
+            "half-open",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.range_bounds, RangeBounds::HalfOpen);
+    }
+
+    #[test]
+    fn parses_negative_overlap_as_gap() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "10",
+            "--sql",
+            "DELETE FROM users",
+            "--overlap",
+            "-2",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.overlap, -2);
+    }
+
+    #[test]
+    fn limit_batching_does_not_require_id_range() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--sql",
+            "DELETE FROM users WHERE status = 'old'",
+            "--batching",
+            "limit",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.batching_mode, BatchingMode::LimitBased);
+    }
+
+    #[test]
+    fn formats_batch_count_with_thousands_separators() {
+        assert_eq!(format_with_thousands_separators(0), "0");
+        assert_eq!(format_with_thousands_separators(999), "999");
+        assert_eq!(format_with_thousands_separators(4_210), "4,210");
+        assert_eq!(format_with_thousands_separators(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn validate_flag_defaults_to_first_and_last_batch() {
+        let parse_validation = |extra_args: &[&str]| {
+            let args = [
+                "sql-id-slicer",
+                "--start-id",
+                "1",
+                "--end-id",
+                "10",
+                "--sql",
+                "DELETE FROM users",
+            ]
+            .into_iter()
+            .chain(extra_args.iter().copied());
+            let args = CliArgs::try_parse_from(args).expect("cli args should parse");
+            collect_command_from_args(args)
+                .expect("command should be created")
+                .validation
+        };
+
+        assert_eq!(parse_validation(&[]), BatchValidation::Off);
+        assert_eq!(
+            parse_validation(&["--validate"]),
+            BatchValidation::FirstAndLast
+        );
+        assert_eq!(
+            parse_validation(&["--validate", "all"]),
+            BatchValidation::All
+        );
+    }
+
+    #[test]
+    fn builds_one_command_per_job_with_shared_flags() {
+        let job_file = build_temp_sql_file(
+            r#"
+            [[jobs]]
+            raw_sql = "DELETE FROM users"
+            start_id = 1
+            end_id = 100
+            output = "users.sql"
+
+            [[jobs]]
+            raw_sql = "DELETE FROM orders"
+            primary_key = "order_id"
+            start_id = 5
+            end_id = 50
+            batch_size = 10
+            dialect = "postgres"
+            output = "orders.sql"
+            "#,
+        );
+        let job_file_arg = job_file
+            .to_str()
+            .expect("temp job path should be valid utf8 for test");
+
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--jobs",
+            job_file_arg,
+            "--transaction",
+            "per-batch",
+        ])
+        .expect("cli args should parse");
+        let CliRequest::Jobs(commands) =
+            collect_request_from_args(args).expect("request should be created")
+        else {
+            panic!("--jobs should produce a jobs request");
+        };
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].raw_sql, "DELETE FROM users");
+        assert_eq!(commands[0].batch_size, DEFAULT_BATCH_SIZE);
+        assert_eq!(commands[1].range_column, "order_id");
+        assert_eq!((commands[1].start_id, commands[1].end_id), (5, 50));
+        assert_eq!(commands[1].batch_size, 10);
+        assert_eq!(commands[1].dialect_kind, SqlDialectKind::PostgreSql);
+        assert_eq!(commands[1].output_path, PathBuf::from("orders.sql"));
+        assert!(
+            commands
+                .iter()
+                .all(|command| command.transaction_mode == TransactionMode::PerBatch)
+        );
+
+        fs::remove_file(job_file).expect("temp job file should be removed");
+    }
+
+    #[test]
+    fn names_the_job_with_an_invalid_entry() {
+        let job_file = build_temp_sql_file(
+            r#"
+            [[jobs]]
+            raw_sql = "DELETE FROM users"
+            start_id = 1
+            end_id = 100
+            output = "users.sql"
+
+            [[jobs]]
+            raw_sql = "DELETE FROM orders"
+            start_id = 1
+            end_id = 100
+            dialect = "oracle"
+            output = "orders.sql"
+            "#,
+        );
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--jobs",
+            job_file
+                .to_str()
+                .expect("temp job path should be valid utf8 for test"),
+        ])
+        .expect("cli args should parse");
+
+        let error = collect_request_from_args(args)
+            .err()
+            .expect("unknown dialect should be rejected");
+        let message = format!("{error:#}");
+        assert!(message.contains("Job 2 is invalid"), "{message}");
+        assert!(message.contains("Unsupported dialect: oracle"), "{message}");
+
+        fs::remove_file(job_file).expect("temp job file should be removed");
+    }
+
+    #[test]
+    fn shards_do_not_require_id_range() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--sql",
+            "DELETE FROM users",
+            "--shards",
+            "4",
+        ])
+        .expect("cli args should parse");
+
+        let request = collect_request_from_args(args).expect("request should be created");
+        let CliRequest::Generate { command, .. } = request else {
+            panic!("shards should not need a bootstrap query");
+        };
+        assert_eq!(command.shard_count, Some(4));
+        assert!(
+            CliArgs::try_parse_from([
+                "sql-id-slicer",
+                "--sql",
+                "DELETE FROM users",
+                "--shards",
+                "4",
+                "--start-id",
+                "1",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_date_range_with_step() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--sql",
+            "DELETE FROM events",
+            "--primary-key",
+            "created_at",
+            "--start-date",
+            "2023-01-01",
+            "--end-date",
+            "2024-01-01",
+            "--step",
+            "1M",
+        ])
+        .expect("cli args should parse");
+
+        let request = collect_request_from_args(args).expect("request should be created");
+        let CliRequest::Generate { command, .. } = request else {
+            panic!("date ranges should not need a bootstrap query");
+        };
+        assert_eq!(
+            command.date_range,
+            Some(DateRange {
+                start_date: "2023-01-01".parse().expect("date should parse"),
+                end_date: "2024-01-01".parse().expect("date should parse"),
+                step: DateStep::Months(1),
+            })
+        );
+        assert!(
+            CliArgs::try_parse_from([
+                "sql-id-slicer",
+                "--sql",
+                "DELETE FROM events",
+                "--start-date",
+                "2023-01-01",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn reads_ids_from_id_file_and_derives_bounds() {
+        let id_file = build_temp_sql_file("42\n7\n\n19\n");
+
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--id-file",
+            id_file
+                .to_str()
+                .expect("temp id path should be valid utf8 for test"),
+            "--sql",
+            "DELETE FROM users",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(command.id_list, Some(vec![42, 7, 19]));
+        assert_eq!(command.start_id, 7);
+        assert_eq!(command.end_id, 42);
+
+        fs::remove_file(id_file).expect("temp id file should be removed");
+    }
+
+    #[test]
+    fn reads_string_ids_from_id_file() {
+        let id_file = build_temp_sql_file("0b5c-41\n\n7f2a-99\n");
+
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--id-file",
+            id_file
+                .to_str()
+                .expect("temp id path should be valid utf8 for test"),
+            "--string-ids",
+            "--sql",
+            "DELETE FROM users",
+        ])
+        .expect("cli args should parse");
+
+        let command = collect_command_from_args(args).expect("command should be created");
+        assert_eq!(
+            command.string_id_list,
+            Some(vec!["0b5c-41".to_string(), "7f2a-99".to_string()])
+        );
+        assert_eq!(command.id_list, None);
+        assert!(
+            CliArgs::try_parse_from([
+                "sql-id-slicer",
+                "--string-ids",
+                "--sql",
+                "DELETE FROM users"
+            ])
+            .is_err()
+        );
+
+        fs::remove_file(id_file).expect("temp id file should be removed");
+    }
+
+    #[test]
+    fn reports_line_number_for_invalid_id_file_entry() {
+        let id_file = build_temp_sql_file("1\n2\nnot-an-id\n");
+
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--id-file",
+            id_file
+                .to_str()
+                .expect("temp id path should be valid utf8 for test"),
+            "--sql",
+            "DELETE FROM users",
+        ])
+        .expect("cli args should parse");
+
+        let error = collect_command_from_args(args).expect_err("invalid id should be rejected");
+        assert!(format!("{error:#}").contains("Line 3 is not a numeric id: not-an-id"));
+
+        fs::remove_file(id_file).expect("temp id file should be removed");
     }
 
     #[test]
@@ -374,6 +1311,64 @@ mod tests {
         fs::remove_file(sql_file).expect("temp sql file should be removed");
     }
 
+    #[test]
+    fn reads_sql_from_stdin_reader() {
+        assert_eq!(
+            read_sql_from_reader("DELETE FROM users\n".as_bytes())
+                .expect("sql should be read from the reader"),
+            "DELETE FROM users\n"
+        );
+        assert!(read_sql_from_reader(" \n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn rejects_stdin_sql_combined_with_inline_sql() {
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "2",
+            "--sql",
+            "SELECT 1",
+            "--sql-file",
+            "-",
+        ])
+        .expect("cli args should parse");
+
+        let error = collect_command_from_args(args).expect_err("should reject dual sql sources");
+        assert!(
+            error
+                .to_string()
+                .contains("Please provide only one of --sql or --sql-file")
+        );
+    }
+
+    #[test]
+    fn requests_range_bootstrap_when_start_and_end_id_are_omitted() {
+        let args = CliArgs::try_parse_from(["sql-id-slicer", "--sql", "DELETE FROM users"])
+            .expect("cli args should parse");
+        assert!(matches!(
+            collect_request_from_args(args).expect("request should be created"),
+            CliRequest::BootstrapRange(_)
+        ));
+
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--start-id",
+            "1",
+            "--end-id",
+            "5",
+            "--sql",
+            "DELETE FROM users",
+        ])
+        .expect("cli args should parse");
+        assert!(matches!(
+            collect_request_from_args(args).expect("request should be created"),
+            CliRequest::Generate { .. }
+        ));
+    }
+
     #[test]
     fn rejects_when_start_or_end_id_missing_in_args_mode() {
         let missing_start =
@@ -398,4 +1393,63 @@ mod tests {
                 .contains("--end-id is required when using argument mode")
         );
     }
+
+    #[test]
+    fn strict_pk_is_an_alias_of_strict_id_column() {
+        for flag in ["--strict-id-column", "--strict-pk"] {
+            let args = CliArgs::try_parse_from([
+                "sql-id-slicer",
+                "--start-id",
+                "1",
+                "--end-id",
+                "10",
+                "--sql",
+                "DELETE FROM users",
+                flag,
+            ])
+            .expect("cli args should parse");
+
+            let command = collect_command_from_args(args).expect("command should be created");
+            assert!(command.strict_id_column, "{flag}");
+        }
+    }
+
+    #[test]
+    fn accepts_dialect_aliases_in_job_files() {
+        let job_file = build_temp_sql_file(
+            r#"
+            [[jobs]]
+            raw_sql = "DELETE FROM users"
+            start_id = 1
+            end_id = 100
+            dialect = "postgresql"
+            output = "users.sql"
+
+            [[jobs]]
+            raw_sql = "DELETE FROM orders"
+            start_id = 1
+            end_id = 100
+            dialect = "sql_server"
+            output = "orders.sql"
+            "#,
+        );
+        let args = CliArgs::try_parse_from([
+            "sql-id-slicer",
+            "--jobs",
+            job_file
+                .to_str()
+                .expect("temp job path should be valid utf8 for test"),
+        ])
+        .expect("cli args should parse");
+        let CliRequest::Jobs(commands) =
+            collect_request_from_args(args).expect("request should be created")
+        else {
+            panic!("--jobs should produce a jobs request");
+        };
+
+        assert_eq!(commands[0].dialect_kind, SqlDialectKind::PostgreSql);
+        assert_eq!(commands[1].dialect_kind, SqlDialectKind::MsSql);
+
+        fs::remove_file(job_file).expect("temp job file should be removed");
+    }
 }
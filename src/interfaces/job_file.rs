@@ -0,0 +1,118 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+
+/// One `[[jobs]]` entry; fields left out fall back to the command-line flags.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobSpec {
+    pub raw_sql: Option<String>,
+    pub sql_file: Option<PathBuf>,
+    #[serde(alias = "primary_key")]
+    pub range_column: Option<String>,
+    /// TOML integers are 64-bit, so job ranges are limited to `i64`.
+    pub start_id: i64,
+    pub end_id: i64,
+    pub batch_size: Option<usize>,
+    pub dialect: Option<String>,
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobFile {
+    jobs: Vec<toml::Table>,
+}
+
+pub fn read_job_file(path: &Path) -> Result<Vec<JobSpec>> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| anyhow!("Unable to read job file {}: {error}", path.display()))?;
+    parse_job_file(&content).with_context(|| format!("Invalid job file: {}", path.display()))
+}
+
+/// Entries are deserialized one by one so an error names the job it belongs to.
+pub fn parse_job_file(content: &str) -> Result<Vec<JobSpec>> {
+    let job_file: JobFile = toml::from_str(content)?;
+    if job_file.jobs.is_empty() {
+        return Err(anyhow!("Job file does not contain any [[jobs]] entries"));
+    }
+
+    job_file
+        .jobs
+        .into_iter()
+        .enumerate()
+        .map(|(job_index, job_table)| {
+            job_table
+                .try_into()
+                .map_err(|error| anyhow!("Job {} is invalid: {error}", job_index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_job_file;
+
+    #[test]
+    fn parses_every_job_entry() {
+        let jobs = parse_job_file(
+            r#"
+            [[jobs]]
+            raw_sql = "DELETE FROM users"
+            range_column = "seq"
+            start_id = 1
+            end_id = 100
+            output = "users.sql"
+
+            [[jobs]]
+            sql_file = "orders.sql"
+            primary_key = "order_id"
+            start_id = 5
+            end_id = 50
+            batch_size = 10
+            dialect = "mysql"
+            output = "orders.sql.out"
+            "#,
+        )
+        .expect("job file should parse");
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].raw_sql.as_deref(), Some("DELETE FROM users"));
+        assert_eq!(jobs[0].batch_size, None);
+        assert_eq!(jobs[0].range_column.as_deref(), Some("seq"));
+        assert_eq!(jobs[1].range_column.as_deref(), Some("order_id"));
+        assert_eq!(jobs[1].sql_file, Some(PathBuf::from("orders.sql")));
+        assert_eq!(jobs[1].dialect.as_deref(), Some("mysql"));
+        assert_eq!((jobs[1].start_id, jobs[1].end_id), (5, 50));
+    }
+
+    #[test]
+    fn names_the_invalid_job() {
+        let error = parse_job_file(
+            r#"
+            [[jobs]]
+            raw_sql = "DELETE FROM users"
+            start_id = 1
+            end_id = 100
+            output = "users.sql"
+
+            [[jobs]]
+            raw_sql = "DELETE FROM orders"
+            start_id = 1
+            output = "orders.sql"
+            "#,
+        )
+        .expect_err("job without end_id should be rejected");
+
+        let message = format!("{error:#}");
+        assert!(message.contains("Job 2 is invalid"), "{message}");
+        assert!(message.contains("end_id"), "{message}");
+        assert!(parse_job_file("jobs = []").is_err());
+    }
+}
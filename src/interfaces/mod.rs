@@ -1 +1,3 @@
 pub mod cli;
+mod job_file;
+pub mod run_summary;
@@ -0,0 +1,188 @@
+use serde::Serialize;
+
+use sql_id_slicer::{BatchingMode, GenerateBatchedSqlCommand, GenerateBatchedSqlResult};
+
+/// The `--summary-json` line; bounds that do not apply to the run are left out.
+#[derive(Debug, Serialize)]
+pub struct RunSummary {
+    /// `-` for stdout output, `null` for a dry run.
+    output: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    files: Vec<String>,
+    batches: usize,
+    /// Only reported when `--limit-batches` cut the run short.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_batches: Option<usize>,
+    truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_id: Option<i128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_id: Option<i128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<String>,
+    dialect: &'static str,
+    dry_run: bool,
+}
+
+/// The parts of the command the summary reports, kept because `execute` consumes the command.
+#[derive(Debug)]
+pub struct RunSummaryBuilder {
+    id_bounds: Option<(i128, i128)>,
+    date_bounds: Option<(String, String)>,
+    write_to_stdout: bool,
+    dialect: &'static str,
+    dry_run: bool,
+}
+
+impl RunSummaryBuilder {
+    pub fn new(command: &GenerateBatchedSqlCommand) -> Self {
+        // Shard, LIMIT and string id batches are not bounded by a numeric id range.
+        let has_id_bounds = command.batching_mode == BatchingMode::RangeBased
+            && command.shard_count.is_none()
+            && command.date_range.is_none()
+            && command.string_id_list.is_none();
+        Self {
+            id_bounds: has_id_bounds.then_some((command.start_id, command.end_id)),
+            date_bounds: command.date_range.map(|date_range| {
+                (
+                    date_range.start_date.to_string(),
+                    date_range.end_date.to_string(),
+                )
+            }),
+            write_to_stdout: command.write_to_stdout,
+            dialect: command.dialect_kind.as_str(),
+            dry_run: command.dry_run,
+        }
+    }
+
+    pub fn build(self, result: &GenerateBatchedSqlResult) -> RunSummary {
+        let output = match &result.output_path {
+            Some(output_path) => Some(output_path.display().to_string()),
+            None if self.write_to_stdout && !self.dry_run => Some("-".to_string()),
+            None => None,
+        };
+        RunSummary {
+            output,
+            // Only split output has numbered files beyond `output` worth listing.
+            files: match result.files_written.as_slice() {
+                [_, _, ..] => result
+                    .files_written
+                    .iter()
+                    .map(|file| file.display().to_string())
+                    .collect(),
+                _ => Vec::new(),
+            },
+            batches: result.batch_count,
+            total_batches: (result.batch_count < result.total_batch_count)
+                .then_some(result.total_batch_count),
+            truncated: result.batch_count < result.total_batch_count,
+            start_id: self.id_bounds.map(|(start_id, _)| start_id),
+            end_id: self.id_bounds.map(|(_, end_id)| end_id),
+            start_date: self
+                .date_bounds
+                .as_ref()
+                .map(|(start_date, _)| start_date.clone()),
+            end_date: self.date_bounds.map(|(_, end_date)| end_date),
+            dialect: self.dialect,
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+impl RunSummary {
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).expect("run summary has only serializable fields")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use sql_id_slicer::{
+        BatchValidation, BatchingMode, GenerateBatchedSqlCommand, GenerateBatchedSqlResult,
+        OutputFormat, RangeBounds, SqlDialectKind, StatementTerminator, TransactionMode,
+    };
+
+    use super::RunSummaryBuilder;
+
+    fn build_command() -> GenerateBatchedSqlCommand {
+        GenerateBatchedSqlCommand {
+            start_id: 1,
+            end_id: 7_100,
+            batch_size: 50,
+            sleep_seconds: 0,
+            raw_sql: "DELETE FROM users".to_string(),
+            output_path: PathBuf::from("id_slice.sql"),
+            write_to_stdout: false,
+            range_column: "id".to_string(),
+            key_prefix: Vec::new(),
+            strict_id_column: false,
+            suppress_warnings: false,
+            quote_identifiers: false,
+            dialect_kind: SqlDialectKind::MySql,
+            transaction_mode: TransactionMode::None,
+            statement_terminator: StatementTerminator::Semicolon,
+            range_bounds: RangeBounds::InclusiveBetween,
+            overlap: 0,
+            id_list: None,
+            string_id_list: None,
+            shard_count: None,
+            date_range: None,
+            split_every: None,
+            batching_mode: BatchingMode::RangeBased,
+            descending: false,
+            annotate: false,
+            batch_limit: None,
+            dry_run: false,
+            output_format: OutputFormat::Sql,
+            validation: BatchValidation::Off,
+            with_verify: false,
+            append: false,
+        }
+    }
+
+    fn build_result(
+        output_path: Option<PathBuf>,
+        files_written: Vec<PathBuf>,
+    ) -> GenerateBatchedSqlResult {
+        GenerateBatchedSqlResult {
+            output_path,
+            batch_count: 142,
+            total_batch_count: 142,
+            files_written,
+            dry_run_preview: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_file_output_as_one_json_line() {
+        let summary = RunSummaryBuilder::new(&build_command()).build(&build_result(
+            Some(PathBuf::from("id_slice.sql")),
+            vec![PathBuf::from("id_slice.sql")],
+        ));
+
+        assert_eq!(
+            summary.to_json_line(),
+            r#"{"output":"id_slice.sql","batches":142,"truncated":false,"start_id":1,"end_id":7100,"dialect":"mysql","dry_run":false}"#
+        );
+    }
+
+    #[test]
+    fn leaves_out_id_bounds_for_shards_and_reports_stdout() {
+        let command = GenerateBatchedSqlCommand {
+            shard_count: Some(4),
+            write_to_stdout: true,
+            ..build_command()
+        };
+
+        let summary = RunSummaryBuilder::new(&command).build(&build_result(None, Vec::new()));
+
+        assert_eq!(
+            summary.to_json_line(),
+            r#"{"output":"-","batches":142,"truncated":false,"dialect":"mysql","dry_run":false}"#
+        );
+    }
+}
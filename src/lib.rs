@@ -0,0 +1,47 @@
+//! Slices one SQL statement into primary-key-based batches.
+//!
+//! The binary is a thin CLI over this library; embedders build a [`GenerateBatchedSqlCommand`]
+//! and either write it to disk with [`GenerateBatchedSqlUseCase::execute`] or consume the
+//! rendered statements in memory with [`render_batched_sql`]. To drive their own execution loop,
+//! they can pair each [`IdBatchRange`] of an [`IdBatchSlicer`] with its statement through
+//! [`SqlParserBatchTemplate::render_all`].
+
+mod application;
+mod domain;
+mod infrastructure;
+
+pub use application::{
+    commands::{
+        BatchValidation, DryRunPreview, GenerateBatchedSqlCommand, GenerateBatchedSqlResult,
+        OutputFormat,
+    },
+    error::GenerateBatchedSqlError,
+    use_cases::generate_batched_sql::GenerateBatchedSqlUseCase,
+};
+pub use domain::{
+    date_batch::{DateRange, DateStep},
+    error::DomainError,
+    id_batch::{BatchingMode, IdBatchRange, IdBatchSlicer, RangeBounds, count_id_range_batches},
+    sql_dialect::SqlDialectKind,
+    statement_terminator::StatementTerminator,
+    transaction_mode::TransactionMode,
+};
+pub use infrastructure::{
+    id_file::{IdFileError, read_id_file, read_string_id_file},
+    sql_batch_template::SqlParserBatchTemplate,
+};
+
+/// Renders `SELECT MIN(pk), MAX(pk) FROM <target table>`, whose result is the id range to slice.
+pub fn render_range_bootstrap_query(
+    command: &GenerateBatchedSqlCommand,
+) -> Result<String, DomainError> {
+    GenerateBatchedSqlUseCase.render_range_bootstrap_query(command)
+}
+
+/// Renders each batch statement of `command` in emission order (ascending ids unless
+/// `descending` is set), without a trailing `;`.
+pub fn render_batched_sql(
+    command: &GenerateBatchedSqlCommand,
+) -> Result<impl Iterator<Item = Result<String, DomainError>> + use<>, DomainError> {
+    GenerateBatchedSqlUseCase.render_batches(command)
+}
@@ -1,26 +1,173 @@
-mod application;
-mod domain;
-mod infrastructure;
 mod interfaces;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use console::style;
+use sql_id_slicer::{
+    GenerateBatchedSqlCommand, GenerateBatchedSqlResult, GenerateBatchedSqlUseCase,
+};
 
-use crate::application::use_cases::generate_batched_sql::GenerateBatchedSqlUseCase;
-use crate::interfaces::cli::collect_generate_command;
+use crate::interfaces::{
+    cli::{CliRequest, collect_cli_request},
+    run_summary::RunSummaryBuilder,
+};
 
 fn main() -> Result<()> {
-    let command = collect_generate_command()?;
     let use_case = GenerateBatchedSqlUseCase;
+    let (command, summary_json) = match collect_cli_request()? {
+        CliRequest::Generate {
+            command,
+            summary_json,
+        } => (command, summary_json),
+        CliRequest::BootstrapRange(command) => {
+            let bootstrap_query = use_case.render_range_bootstrap_query(&command)?;
+            eprintln!(
+                "{}",
+                style("No --start-id/--end-id given; run this query first:").yellow()
+            );
+            println!(
+                "{}",
+                command
+                    .dialect_kind
+                    .line_comment("sql-id-slicer bootstrap query, not a batch")
+            );
+            println!("{bootstrap_query};");
+            eprintln!(
+                "{}",
+                style("Then re-run with --start-id <MIN> --end-id <MAX>.").dim()
+            );
+            return Ok(());
+        }
+        CliRequest::Cancelled => {
+            println!("{}", style("Cancelled; nothing was written.").yellow());
+            return Ok(());
+        }
+        CliRequest::Jobs(commands) => return run_jobs(&use_case, commands),
+    };
 
-    println!("{}", style("Generating batched SQL...").cyan());
+    if summary_json {
+        let summary_builder = RunSummaryBuilder::new(&command);
+        let result = use_case.execute(command)?;
+        // The dry-run report goes to stdout as usual, so stderr still holds only the JSON line.
+        if result.output_path.is_none() {
+            print_dry_run_report(&result);
+        }
+        eprintln!("{}", summary_builder.build(&result).to_json_line());
+        return Ok(());
+    }
+
+    // With stdout output the SQL itself goes to stdout, so progress messages go to stderr.
+    let write_to_stdout = command.write_to_stdout && !command.dry_run;
+    if write_to_stdout {
+        eprintln!("{}", style("Generating batched SQL...").cyan());
+    } else {
+        println!("{}", style("Generating batched SQL...").cyan());
+    }
     let result = use_case.execute(command)?;
 
+    if write_to_stdout {
+        eprintln!(
+            "{} ({})",
+            style("Generated SQL has been written to stdout").green(),
+            describe_batch_count(&result),
+        );
+        return Ok(());
+    }
+    let Some(output_path) = &result.output_path else {
+        print_dry_run_report(&result);
+        return Ok(());
+    };
+
+    match result.files_written.as_slice() {
+        [first_file, .., last_file] => println!(
+            "{} {} files, {} .. {} ({})",
+            style("Generated SQL has been saved to").green(),
+            result.files_written.len(),
+            style(first_file.display()).bold(),
+            style(last_file.display()).bold(),
+            describe_batch_count(&result),
+        ),
+        files_written => println!(
+            "{} {} ({})",
+            style("Generated SQL has been saved to").green(),
+            style(files_written.first().unwrap_or(output_path).display()).bold(),
+            describe_batch_count(&result),
+        ),
+    }
+    Ok(())
+}
+
+fn print_dry_run_report(result: &GenerateBatchedSqlResult) {
+    println!(
+        "{} {} would be generated",
+        style("Dry run:").yellow(),
+        describe_batch_count(result)
+    );
+    if let Some(preview) = &result.dry_run_preview {
+        println!(
+            "{}\n{}",
+            style("First batch:").dim(),
+            preview.first_batch_sql
+        );
+        println!("{}\n{}", style("Last batch:").dim(), preview.last_batch_sql);
+    }
+}
+
+fn describe_batch_count(result: &GenerateBatchedSqlResult) -> String {
+    if result.batch_count < result.total_batch_count {
+        format!(
+            "{} of {} batches, truncated by --limit-batches",
+            result.batch_count, result.total_batch_count
+        )
+    } else {
+        format!("{} batches", result.batch_count)
+    }
+}
+
+/// Stops at the first failing job; jobs before it have already written their files.
+fn run_jobs(
+    use_case: &GenerateBatchedSqlUseCase,
+    commands: Vec<GenerateBatchedSqlCommand>,
+) -> Result<()> {
+    println!(
+        "{}",
+        style(format!(
+            "Generating batched SQL for {} jobs...",
+            commands.len()
+        ))
+        .cyan()
+    );
+    let mut results = Vec::with_capacity(commands.len());
+    for (job_index, command) in commands.into_iter().enumerate() {
+        let result = use_case
+            .execute(command)
+            .with_context(|| format!("Job {} failed", job_index + 1))?;
+        results.push(result);
+    }
+
+    for (job_index, result) in results.iter().enumerate() {
+        match &result.output_path {
+            Some(output_path) => println!(
+                "{} {} batches -> {}",
+                style(format!("Job {}:", job_index + 1)).green(),
+                result.batch_count,
+                style(output_path.display()).bold(),
+            ),
+            None => println!(
+                "{} {} batches would be generated",
+                style(format!("Job {}:", job_index + 1)).yellow(),
+                result.batch_count,
+            ),
+        }
+    }
+    let total_batch_count = results
+        .iter()
+        .map(|result| result.batch_count)
+        .sum::<usize>();
     println!(
-        "{} {} ({} batches)",
-        style("Generated SQL has been saved to").green(),
-        style(result.output_path.display()).bold(),
-        result.batch_count,
+        "{} {} batches across {} jobs",
+        style("Total:").green(),
+        total_batch_count,
+        results.len()
     );
     Ok(())
 }
@@ -0,0 +1,33 @@
+use std::process::Command;
+
+#[test]
+fn summary_json_dry_run_keeps_the_preview_on_stdout() {
+    let output = Command::new(env!("CARGO_BIN_EXE_sql-id-slicer"))
+        .args([
+            "--sql",
+            "DELETE FROM users WHERE active = 0",
+            "--start-id",
+            "1",
+            "--end-id",
+            "100",
+            "--batch-size",
+            "50",
+            "--dry-run",
+            "--summary-json",
+        ])
+        .output()
+        .expect("binary should run");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    assert!(stdout.contains("Dry run: 2 batches would be generated"));
+    assert!(stdout.contains("DELETE FROM users WHERE id BETWEEN 1 AND 50 AND (active = 0)"));
+    assert!(stdout.contains("DELETE FROM users WHERE id BETWEEN 51 AND 100 AND (active = 0)"));
+
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be utf-8");
+    let summary: serde_json::Value =
+        serde_json::from_str(stderr.trim_end()).expect("stderr should be one JSON line");
+    assert_eq!(stderr.lines().count(), 1);
+    assert_eq!(summary["dry_run"], true);
+    assert_eq!(summary["output"], serde_json::Value::Null);
+}
@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use sql_id_slicer::{
+    BatchValidation, BatchingMode, DomainError, GenerateBatchedSqlCommand, GenerateBatchedSqlError,
+    GenerateBatchedSqlUseCase, IdBatchRange, IdBatchSlicer, OutputFormat, RangeBounds,
+    SqlDialectKind, SqlParserBatchTemplate, StatementTerminator, TransactionMode,
+    render_batched_sql,
+};
+
+fn build_update_command() -> GenerateBatchedSqlCommand {
+    GenerateBatchedSqlCommand {
+        start_id: 1,
+        end_id: 120,
+        batch_size: 50,
+        sleep_seconds: 0,
+        raw_sql: "UPDATE users u SET active = 0 WHERE status = 'old'".to_string(),
+        output_path: PathBuf::from("unused.sql"),
+        write_to_stdout: false,
+        range_column: "id".to_string(),
+        key_prefix: Vec::new(),
+        strict_id_column: false,
+        suppress_warnings: false,
+        quote_identifiers: false,
+        dialect_kind: SqlDialectKind::PostgreSql,
+        transaction_mode: TransactionMode::None,
+        statement_terminator: StatementTerminator::Semicolon,
+        range_bounds: RangeBounds::InclusiveBetween,
+        overlap: 0,
+        id_list: None,
+        string_id_list: None,
+        shard_count: None,
+        date_range: None,
+        split_every: None,
+        batching_mode: BatchingMode::RangeBased,
+        descending: false,
+        annotate: false,
+        batch_limit: None,
+        dry_run: false,
+        output_format: OutputFormat::Sql,
+        validation: BatchValidation::Off,
+        with_verify: false,
+        append: false,
+    }
+}
+
+#[test]
+fn renders_batches_through_public_api() {
+    let command = build_update_command();
+
+    let rendered = render_batched_sql(&command)
+        .expect("batches should be prepared")
+        .collect::<Result<Vec<_>, DomainError>>()
+        .expect("batches should be rendered");
+
+    assert_eq!(
+        rendered,
+        vec![
+            "UPDATE users u SET active = 0 WHERE u.id BETWEEN 1 AND 50 AND (status = 'old')",
+            "UPDATE users u SET active = 0 WHERE u.id BETWEEN 51 AND 100 AND (status = 'old')",
+            "UPDATE users u SET active = 0 WHERE u.id BETWEEN 101 AND 120 AND (status = 'old')",
+        ]
+    );
+}
+
+#[test]
+fn pairs_each_id_range_with_its_statement() {
+    let template = SqlParserBatchTemplate::parse(
+        "DELETE FROM users WHERE status = 'old'",
+        SqlDialectKind::PostgreSql,
+        "id",
+        &[],
+        RangeBounds::InclusiveBetween,
+        BatchingMode::RangeBased,
+        false,
+    )
+    .expect("template should be parsed");
+    let slicer = IdBatchSlicer::new(1, 75, 50, RangeBounds::InclusiveBetween)
+        .expect("slicer should be created")
+        .in_descending_order();
+
+    let batches = template
+        .render_all(&slicer)
+        .collect::<Result<Vec<_>, DomainError>>()
+        .expect("batches should be rendered");
+
+    assert_eq!(
+        batches,
+        vec![
+            (
+                IdBatchRange {
+                    start_id: 51,
+                    end_id: 75,
+                },
+                "DELETE FROM users WHERE id BETWEEN 51 AND 75 AND (status = 'old')".to_string()
+            ),
+            (
+                IdBatchRange {
+                    start_id: 1,
+                    end_id: 50,
+                },
+                "DELETE FROM users WHERE id BETWEEN 1 AND 50 AND (status = 'old')".to_string()
+            ),
+        ]
+    );
+    assert_eq!((slicer.start_id(), slicer.end_id()), (1, 75));
+    assert!(slicer.is_descending());
+}
+
+#[test]
+fn distinguishes_parse_failures_from_io_failures() {
+    let parse_failure = GenerateBatchedSqlUseCase
+        .execute(GenerateBatchedSqlCommand {
+            raw_sql: "UPDATE users SET".to_string(),
+            ..build_update_command()
+        })
+        .expect_err("invalid sql should be rejected");
+    assert!(matches!(
+        parse_failure,
+        GenerateBatchedSqlError::Domain(DomainError::ParseFailed(_))
+    ));
+
+    let io_failure = GenerateBatchedSqlUseCase
+        .execute(GenerateBatchedSqlCommand {
+            output_path: std::env::temp_dir().join("sql_id_slicer_missing_dir/out.sql"),
+            ..build_update_command()
+        })
+        .expect_err("missing output directory should be rejected");
+    assert!(matches!(
+        io_failure,
+        GenerateBatchedSqlError::CreateFile { .. }
+    ));
+}